@@ -12,7 +12,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use sage_x_mcp_client::{
-    client::{SageXClient, SageXEvent},
+    client::SageXClient,
     models::{
         SageXConfig, SessionContext, McpTool, McpResource,
         CacheConfig, NetworkConfig, RulesConfig, FeatureFlags,
@@ -77,19 +77,25 @@ async fn main() -> SageXResult<()> {
         println!("  - {}: {}", resource.name, resource.description.as_deref().unwrap_or("N/A"));
     }
 
-    // 11. Executar uma ferramenta MCP
+    // 11. Executar uma ferramenta MCP (vai falhar pois não há conexão MCP)
     println!("\n🔧 Executando ferramenta 'code_formatter'...");
-    let tool_result = client.execute_tool("code_formatter", json!({
+    match client.execute_tool("code_formatter", json!({
         "language": "rust",
         "file_path": "src/main.rs",
         "format_options": {
             "line_width": 100,
             "tab_spaces": 4
         }
-    })).await?;
-    
-    if let Some(result) = tool_result.result {
-        println!("✅ Resultado da ferramenta: {}", result);
+    })).await {
+        Ok(result) if result.is_error => {
+            println!("⚠️  Ferramenta falhou: {}", result.text());
+        }
+        Ok(result) => {
+            println!("✅ Resultado da ferramenta: {}", result.text());
+        }
+        Err(e) => {
+            println!("❌ Erro ao executar ferramenta: {}", e);
+        }
     }
 
     // 12. Simular carregamento de regras (vai falhar pois não há servidor)
@@ -203,6 +209,7 @@ async fn create_custom_config() -> SageXConfig {
                 prompts: true,
                 notifications: true,
                 streaming: false,
+                ..Default::default()
             },
             transport: sage_x_mcp_client::models::TransportConfig {
                 transport_type: sage_x_mcp_client::models::TransportType::Http,
@@ -213,6 +220,7 @@ async fn create_custom_config() -> SageXConfig {
                     config
                 },
             },
+            roots: Vec::new(),
         },
         
         telemetry: TelemetryConfig {
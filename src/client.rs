@@ -2,19 +2,32 @@
 //!
 //! Implementa o protocolo MCP e integração com o sistema de regras SAGE-X.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use reqwest::{Client as HttpClient, header::{HeaderMap, HeaderValue, HeaderName, AUTHORIZATION, USER_AGENT}};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{broadcast, Mutex, RwLock, mpsc};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 use crate::error::{SageXError, SageXResult};
+use crate::mcp::messages::error_codes;
+use crate::mcp::{
+    CancellationToken, CompleteParams, Completer, Completion, CompletionOptions, ConnectionEvent, ElicitationRequestHandler,
+    GetPromptResult, HandlerRegistry, HeartbeatConfig, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+    ListToolsResult, LogLevel, LogRecord, McpConnection, McpNotification, McpPrompt, Paginated, ReadResourceResult, ReconnectPolicy,
+    RequestOptions, ResourceSubscription, ResourceSubscriptions, ResourceTemplate, ResourceUpdated, RootsProvider,
+    SamplingRequestHandler, ToolCallResult, Transport,
+};
+use crate::mcp::protocol::WeakConnection;
+use crate::mcp::roots::ROOTS_LIST_CHANGED;
+use crate::retry::{send_with_retry, Backoff, RetryPolicy};
 use crate::models::{
     SageXConfig, SageXRule, DevSession, SessionContext, SessionState,
-    McpRequest, McpResponse, McpTool, McpResource,
+    McpCapabilities, McpRequest, McpTool, McpResource,
     ExecutionResult
 };
 
@@ -39,11 +52,57 @@ pub struct SageXClient {
     /// Receiver para eventos internos
     event_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<SageXEvent>>>>,
     
-    /// Ferramentas MCP disponíveis
+    /// Ferramentas MCP descobertas no servidor
     available_tools: Arc<RwLock<Vec<McpTool>>>,
     
-    /// Resources MCP disponíveis
+    /// Resources MCP descobertos no servidor
     available_resources: Arc<RwLock<Vec<McpResource>>>,
+    
+    /// Prompts descobertos no servidor
+    available_prompts: Arc<RwLock<Vec<McpPrompt>>>,
+    
+    /// Ferramentas registradas localmente (sobrepõem as do servidor)
+    local_tools: Arc<RwLock<Vec<McpTool>>>,
+    
+    /// Resources registrados localmente (sobrepõem os do servidor)
+    local_resources: Arc<RwLock<Vec<McpResource>>>,
+    
+    /// Conexão MCP com o servidor de ferramentas
+    mcp_connection: Arc<RwLock<Option<McpConnection>>>,
+    
+    /// Handlers para requests iniciados pelo servidor, mantidos entre conexões
+    request_handlers: HandlerRegistry,
+    
+    /// Task que atualiza o catálogo em `notifications/*/list_changed`
+    catalog_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    
+    /// Roots anunciadas ao servidor em `roots/list`
+    roots: RootsProvider,
+    
+    /// Resources assinados, mantidos entre conexões
+    resource_subscriptions: ResourceSubscriptions,
+    
+    /// Serializa assinaturas: quem chega durante um `resources/subscribe`
+    /// aguarda o resultado antes de compartilhar a assinatura
+    subscription_lock: Arc<Mutex<()>>,
+    
+    /// Debounce e cache de `completion/complete`
+    completer: Completer,
+    
+    /// Repassar os logs do servidor ao crate `log`
+    forward_server_logs: bool,
+    
+    /// Task que repassa os logs do servidor
+    log_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    
+    /// Heartbeat aplicado às conexões MCP
+    heartbeat: Option<HeartbeatConfig>,
+    
+    /// Reconexão das conexões MCP; sem valor, as conexões não reconectam
+    reconnect: Option<ReconnectPolicy>,
+    
+    /// Retentativa das chamadas HTTP; sem valor, derivada de `NetworkConfig`
+    retry: Option<RetryPolicy>,
 }
 
 /// Eventos internos do sistema
@@ -101,6 +160,13 @@ pub enum SageXEvent {
 pub struct SageXClientBuilder {
     config: Option<SageXConfig>,
     custom_http_client: Option<HttpClient>,
+    transport: Option<Box<dyn Transport>>,
+    request_handlers: HandlerRegistry,
+    completion_options: CompletionOptions,
+    forward_server_logs: bool,
+    heartbeat: Option<HeartbeatConfig>,
+    reconnect: Option<ReconnectPolicy>,
+    retry: Option<RetryPolicy>,
     disable_cache: bool,
     disable_telemetry: bool,
 }
@@ -123,6 +189,64 @@ impl SageXClientBuilder {
         self
     }
 
+    /// Define o transporte MCP, conectado durante o `build`
+    pub fn with_transport(mut self, transport: Box<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Atende pedidos de sampling do servidor com o handler informado
+    ///
+    /// A capacidade `sampling` é anunciada ao conectar.
+    pub fn with_sampling(self, handler: SamplingRequestHandler) -> Self {
+        handler.register(&self.request_handlers);
+        self
+    }
+
+    /// Atende pedidos de elicitation do servidor com o handler informado
+    ///
+    /// A capacidade `elicitation` é anunciada ao conectar.
+    pub fn with_elicitation(self, handler: ElicitationRequestHandler) -> Self {
+        handler.register(&self.request_handlers);
+        self
+    }
+
+    /// Define o debounce e o cache das completações de argumentos
+    pub fn with_completion_options(mut self, options: CompletionOptions) -> Self {
+        self.completion_options = options;
+        self
+    }
+
+    /// Repassa os logs do servidor ao crate `log`, com o nome do servidor como target
+    pub fn forward_server_logs(mut self) -> Self {
+        self.forward_server_logs = true;
+        self
+    }
+
+    /// Habilita pings periódicos para detectar conexões MCP sem resposta
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatConfig) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+
+    /// Habilita a reconexão automática das conexões MCP
+    ///
+    /// Sem esta opção, uma conexão perdida não é restabelecida. Transportes
+    /// que não podem ser reabertos (stdio) nunca reconectam.
+    /// `Backoff::from_network` reaproveita `max_retries` e `retry_delay`.
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+
+    /// Define a retentativa das chamadas HTTP
+    ///
+    /// Por padrão usa `max_retries` e `retry_delay` de `NetworkConfig`.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
     /// Desabilita o cache
     pub fn disable_cache(mut self) -> Self {
         self.disable_cache = true;
@@ -152,7 +276,10 @@ impl SageXClientBuilder {
 
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
 
-        Ok(SageXClient {
+        let roots = RootsProvider::with_directories(&config.mcp.roots)?;
+        roots.register(&self.request_handlers);
+
+        let client = SageXClient {
             config: Arc::new(RwLock::new(config)),
             http_client,
             rules_cache: Arc::new(RwLock::new(HashMap::new())),
@@ -161,7 +288,28 @@ impl SageXClientBuilder {
             event_receiver: Arc::new(RwLock::new(Some(event_receiver))),
             available_tools: Arc::new(RwLock::new(Vec::new())),
             available_resources: Arc::new(RwLock::new(Vec::new())),
-        })
+            available_prompts: Arc::new(RwLock::new(Vec::new())),
+            local_tools: Arc::new(RwLock::new(Vec::new())),
+            local_resources: Arc::new(RwLock::new(Vec::new())),
+            mcp_connection: Arc::new(RwLock::new(None)),
+            request_handlers: self.request_handlers,
+            catalog_task: Arc::new(Mutex::new(None)),
+            roots,
+            resource_subscriptions: ResourceSubscriptions::new(),
+            subscription_lock: Arc::new(Mutex::new(())),
+            completer: Completer::new(self.completion_options),
+            forward_server_logs: self.forward_server_logs,
+            log_task: Arc::new(Mutex::new(None)),
+            heartbeat: self.heartbeat,
+            reconnect: self.reconnect,
+            retry: self.retry,
+        };
+
+        if let Some(transport) = self.transport {
+            client.connect_mcp(transport).await?;
+        }
+
+        Ok(client)
     }
 }
 
@@ -223,7 +371,13 @@ impl SageXClient {
     }
 
     /// Inicia uma nova sessão de desenvolvimento
+    ///
+    /// Falha, sem alterar a sessão atual, se o diretório de trabalho não puder
+    /// ser anunciado como root.
     pub async fn start_session(&self, context: SessionContext) -> SageXResult<Uuid> {
+        // Anunciar o diretório da sessão como root
+        self.roots.set_session(&context)?;
+
         let session_id = Uuid::new_v4();
         let started_at = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
@@ -253,6 +407,8 @@ impl SageXClient {
             *current_session = Some(session);
         }
 
+        self.notify_roots_changed().await;
+
         // Emitir evento
         let _ = self.event_sender.send(SageXEvent::SessionStarted {
             session_id,
@@ -292,6 +448,10 @@ impl SageXClient {
             }
         };
 
+        if self.roots.clear_session() {
+            self.notify_roots_changed().await;
+        }
+
         Ok(session_id)
     }
 
@@ -302,21 +462,15 @@ impl SageXClient {
 
     /// Carrega regras do servidor remoto
     pub async fn load_rules(&self) -> SageXResult<Vec<SageXRule>> {
-        let config = self.config.read().await;
-        let url = format!("{}/rules", config.api_base_url);
-        
-        let response = self.http_client
-            .get(&url)
-            .timeout(config.network.request_timeout)
-            .send()
-            .await
-            .map_err(|e| SageXError::connection(format!("Falha ao conectar: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(SageXError::Http(
-                response.error_for_status().unwrap_err().to_string()
-            ));
-        }
+        // A configuração não fica bloqueada durante as retentativas
+        let (request, policy) = {
+            let config = self.config.read().await;
+            let request = self.http_client
+                .get(format!("{}/rules", config.api_base_url))
+                .timeout(config.network.request_timeout);
+            (request, self.retry_policy(&config))
+        };
+        let response = send_with_retry(&policy, request, "GET /rules").await?;
 
         let rules: Vec<SageXRule> = response
             .json()
@@ -433,78 +587,310 @@ impl SageXClient {
         Ok(results)
     }
 
-    /// Executa uma ferramenta MCP
-    pub async fn execute_tool(&self, tool_name: &str, params: Value) -> SageXResult<McpResponse> {
-        let request_id = Uuid::new_v4().to_string();
-        let timestamp = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    /// Conecta ao servidor MCP através do transporte informado
+    ///
+    /// Uma conexão anterior, se existir, é encerrada antes. Após conectar,
+    /// descobre as ferramentas e resources do servidor e passa a atualizá-los
+    /// quando o servidor notificar mudanças.
+    pub async fn connect_mcp(&self, transport: Box<dyn Transport>) -> SageXResult<()> {
+        self.disconnect_mcp().await?;
+
+        let request_timeout = self.config.read().await.network.request_timeout;
+        let mut connection = McpConnection::new(transport, McpCapabilities::default()).await?
+            .with_request_timeout(request_timeout)
+            .with_handlers(self.request_handlers.clone());
+        if let Some(reconnect) = self.reconnect.clone() {
+            connection = connection.with_reconnect(reconnect);
+        }
+        if let Some(heartbeat) = self.heartbeat.clone() {
+            connection = connection.with_heartbeat(heartbeat);
+        }
+        connection.connect().await?;
 
-        let _request = McpRequest {
-            id: request_id.clone(),
-            method: format!("tools/{}", tool_name),
-            params: Some(params),
-            timestamp,
-        };
+        // Assinar antes da descoberta para não perder notificações
+        let notifications = connection.subscribe_notifications();
+        let events = connection.subscribe_events();
+        if self.forward_server_logs {
+            *self.log_task.lock().await = Some(connection.forward_logs().await);
+        }
+        *self.mcp_connection.write().await = Some(connection.clone());
+
+        if let Err(e) = discover::<ListToolsResult>(&connection, "tools/list", &self.available_tools).await {
+            log::warn!("Falha ao descobrir ferramentas MCP: {}", e);
+        }
+        if let Err(e) = discover::<ListResourcesResult>(&connection, "resources/list", &self.available_resources).await {
+            log::warn!("Falha ao descobrir resources MCP: {}", e);
+        }
+        if let Err(e) = discover::<ListPromptsResult>(&connection, "prompts/list", &self.available_prompts).await {
+            log::warn!("Falha ao descobrir prompts MCP: {}", e);
+        }
+
+        let task = tokio::spawn(watch_catalog(
+            connection.downgrade(),
+            notifications,
+            events,
+            self.available_tools.clone(),
+            self.available_resources.clone(),
+            self.available_prompts.clone(),
+            self.resource_subscriptions.clone(),
+        ));
+        *self.catalog_task.lock().await = Some(task);
+
+        Ok(())
+    }
+
+    /// Encerra a conexão MCP atual, se houver
+    pub async fn disconnect_mcp(&self) -> SageXResult<()> {
+        if let Some(task) = self.catalog_task.lock().await.take() {
+            task.abort();
+        }
+        if let Some(task) = self.log_task.lock().await.take() {
+            task.abort();
+        }
+
+        let connection = self.mcp_connection.write().await.take();
+        if let Some(connection) = connection {
+            self.available_tools.write().await.clear();
+            self.available_resources.write().await.clear();
+            self.available_prompts.write().await.clear();
+            self.completer.clear();
+            connection.disconnect().await?;
+        }
+        Ok(())
+    }
+
+    /// Atualiza as ferramentas descobertas via `tools/list`
+    pub async fn refresh_tools(&self) -> SageXResult<Vec<McpTool>> {
+        let connection = self.require_connection().await?;
+        discover::<ListToolsResult>(&connection, "tools/list", &self.available_tools).await?;
+        Ok(self.list_tools().await)
+    }
+
+    /// Atualiza os resources descobertos via `resources/list`
+    pub async fn refresh_resources(&self) -> SageXResult<Vec<McpResource>> {
+        let connection = self.require_connection().await?;
+        discover::<ListResourcesResult>(&connection, "resources/list", &self.available_resources).await?;
+        Ok(self.list_resources().await)
+    }
+
+    /// Obtém o registro de handlers para requests iniciados pelo servidor
+    ///
+    /// Os handlers valem para a conexão atual e as seguintes.
+    pub fn request_handlers(&self) -> &HandlerRegistry {
+        &self.request_handlers
+    }
 
-        // Simular execução da ferramenta
-        // Em uma implementação real, isso seria enviado através do transporte MCP
-        let response = McpResponse {
-            id: request_id,
-            result: Some(serde_json::json!({
-                "success": true,
-                "message": format!("Ferramenta '{}' executada com sucesso", tool_name)
-            })),
-            error: None,
-            timestamp: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+    /// Obtém as roots anunciadas ao servidor
+    ///
+    /// Após alterar os diretórios extras, chame `notify_roots_changed`.
+    pub fn roots(&self) -> &RootsProvider {
+        &self.roots
+    }
+
+    /// Avisa o servidor conectado que as roots mudaram
+    ///
+    /// Sem conexão ativa não há o que avisar; falhas de envio são apenas registradas.
+    pub async fn notify_roots_changed(&self) {
+        let Some(connection) = self.mcp_connection().await else {
+            return;
         };
+        let notification = McpNotification::new(ROOTS_LIST_CHANGED.to_string(), None);
+        if let Err(e) = connection.send_notification(notification).await {
+            log::warn!("Falha ao notificar mudança de roots: {}", e);
+        }
+    }
+
+    /// Obtém um handle para a conexão MCP atual
+    pub async fn mcp_connection(&self) -> Option<McpConnection> {
+        self.mcp_connection.read().await.clone()
+    }
+
+    /// Obtém a conexão MCP ativa ou falha
+    async fn require_connection(&self) -> SageXResult<McpConnection> {
+        self.mcp_connection().await
+            .ok_or_else(|| SageXError::connection("Nenhuma conexão MCP ativa"))
+    }
+
+    /// Executa uma ferramenta MCP via `tools/call`
+    ///
+    /// Erros de protocolo retornam `SageXError::McpRemote`; falhas da própria
+    /// ferramenta retornam `Ok` com `is_error = true`.
+    pub async fn execute_tool(&self, tool_name: &str, params: Value) -> SageXResult<ToolCallResult> {
+        self.execute_tool_with_cancellation(tool_name, params, CancellationToken::new()).await
+    }
+
+    /// Executa uma ferramenta MCP que pode ser abortada através do token
+    ///
+    /// Ao cancelar, o servidor é notificado e o resultado é `SageXError::Cancelled`.
+    pub async fn execute_tool_with_cancellation(
+        &self,
+        tool_name: &str,
+        params: Value,
+        cancel: CancellationToken,
+    ) -> SageXResult<ToolCallResult> {
+        self.execute_tool_with_options(tool_name, params, RequestOptions::new().with_cancellation(cancel)).await
+    }
 
-        Ok(response)
+    /// Executa uma ferramenta MCP com timeout e cancelamento configuráveis
+    ///
+    /// Útil para ferramentas demoradas: combine `reset_timeout_on_progress`
+    /// com `with_max_total_timeout` para limitar apenas a inatividade.
+    pub async fn execute_tool_with_options(
+        &self,
+        tool_name: &str,
+        params: Value,
+        options: RequestOptions,
+    ) -> SageXResult<ToolCallResult> {
+        let connection = self.require_connection().await?;
+
+        let request = McpRequest::call_tool(
+            Uuid::new_v4().to_string(),
+            tool_name.to_string(),
+            params,
+        );
+
+        let result = connection.send_request_with_options(request, options).await?.into_result()?;
+
+        serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de tools/call: {}", e)))
     }
 
-    /// Lista ferramentas MCP disponíveis
+    /// Lista ferramentas MCP disponíveis (servidor + registros locais)
     pub async fn list_tools(&self) -> Vec<McpTool> {
-        self.available_tools.read().await.clone()
+        let discovered = self.available_tools.read().await;
+        let local = self.local_tools.read().await;
+        merge_overrides(&discovered, &local, |tool| tool.name.as_str())
     }
 
-    /// Lista resources MCP disponíveis
+    /// Lista resources MCP disponíveis (servidor + registros locais)
     pub async fn list_resources(&self) -> Vec<McpResource> {
-        self.available_resources.read().await.clone()
+        let discovered = self.available_resources.read().await;
+        let local = self.local_resources.read().await;
+        merge_overrides(&discovered, &local, |resource| resource.uri.as_str())
     }
 
-    /// Obtém um resource específico
-    pub async fn get_resource(&self, uri: &str) -> SageXResult<Value> {
-        let config = self.config.read().await;
-        let url = format!("{}/resources/{}", config.api_base_url, uri);
-        
-        let response = self.http_client
-            .get(&url)
-            .timeout(config.network.request_timeout)
-            .send()
-            .await
-            .map_err(|e| SageXError::connection(format!("Falha ao obter resource: {}", e)))?;
+    /// Lista os prompts do servidor, mantidos em cache
+    ///
+    /// O cache é atualizado em `notifications/prompts/list_changed`.
+    pub async fn list_prompts(&self) -> Vec<McpPrompt> {
+        self.available_prompts.read().await.clone()
+    }
 
-        if !response.status().is_success() {
-            return Err(SageXError::Http(
-                response.error_for_status().unwrap_err().to_string()
-            ));
+    /// Atualiza os prompts descobertos via `prompts/list`
+    pub async fn refresh_prompts(&self) -> SageXResult<Vec<McpPrompt>> {
+        let connection = self.require_connection().await?;
+        discover::<ListPromptsResult>(&connection, "prompts/list", &self.available_prompts).await?;
+        Ok(self.list_prompts().await)
+    }
+
+    /// Renderiza um prompt do servidor via `prompts/get`
+    ///
+    /// Os argumentos são validados contra os declarados pelo prompt antes do
+    /// envio; um prompt fora do cache provoca uma nova listagem.
+    pub async fn get_prompt(&self, name: &str, arguments: HashMap<String, String>) -> SageXResult<GetPromptResult> {
+        let connection = self.require_connection().await?;
+
+        let cached = self.available_prompts.read().await.iter().find(|p| p.name == name).cloned();
+        let prompt = match cached {
+            Some(prompt) => prompt,
+            None => self.refresh_prompts().await?
+                .into_iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| SageXError::validation("prompt", format!("Prompt desconhecido: {}", name)))?,
+        };
+        prompt.validate_arguments(&arguments)?;
+
+        let request = McpRequest::get_prompt(Uuid::new_v4().to_string(), name.to_string(), arguments);
+        let result = connection.send_request(request).await?.into_result()?;
+
+        serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de prompts/get: {}", e)))
+    }
+
+    /// Sugere valores para um argumento de prompt ou variável de resource template
+    ///
+    /// Pensado para ser chamado a cada tecla: pedidos superados por um mais
+    /// recente para o mesmo argumento terminam com `SageXError::Cancelled`, e
+    /// respostas recentes são reaproveitadas sem consultar o servidor.
+    pub async fn complete(&self, params: CompleteParams) -> SageXResult<Completion> {
+        let connection = self.require_connection().await?;
+        self.completer.complete(&connection, params).await
+    }
+
+    /// Define o nível mínimo dos logs enviados pelo servidor
+    pub async fn set_log_level(&self, level: LogLevel) -> SageXResult<()> {
+        self.require_connection().await?.set_log_level(level).await
+    }
+
+    /// Stream dos logs enviados pelo servidor conectado
+    pub async fn server_logs(&self) -> SageXResult<impl futures::Stream<Item = LogRecord> + Send + 'static> {
+        Ok(self.require_connection().await?.log_records())
+    }
+
+    /// Lê um resource do servidor via `resources/read`
+    pub async fn get_resource(&self, uri: &str) -> SageXResult<ReadResourceResult> {
+        let connection = self.require_connection().await?;
+
+        let request = McpRequest::read_resource(Uuid::new_v4().to_string(), uri.to_string());
+        let result = connection.send_request(request).await?.into_result()?;
+
+        serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de resources/read: {}", e)))
+    }
+
+    /// Lista os resource templates do servidor via `resources/templates/list`
+    ///
+    /// Use `ResourceTemplate::expand` para obter a URI a ler.
+    pub async fn list_resource_templates(&self) -> SageXResult<Vec<ResourceTemplate>> {
+        let connection = self.require_connection().await?;
+        fetch_all::<ListResourceTemplatesResult>(&connection, "resources/templates/list").await
+    }
+
+    /// Assina as atualizações de um resource
+    ///
+    /// O servidor é avisado apenas na primeira assinatura da URI; o stream
+    /// recebe cada `notifications/resources/updated` até `unsubscribe_resource`.
+    pub async fn subscribe_resource(&self, uri: &str) -> SageXResult<ResourceSubscription> {
+        let connection = self.require_connection().await?;
+
+        let supported = connection.capabilities().await
+            .is_none_or(|c| c.resources.is_some_and(|r| r.subscribe));
+        if !supported {
+            return Err(SageXError::mcp_protocol("Servidor não suporta assinatura de resources"));
         }
 
-        let resource_data: Value = response
-            .json()
-            .await
-            .map_err(|e| SageXError::serialization(format!("Falha ao deserializar resource: {}", e)))?;
+        // Se o primeiro request falhar, a URI é removida antes de outro assinante entrar
+        let _guard = self.subscription_lock.lock().await;
+        let (subscription, first) = self.resource_subscriptions.add(uri);
+        if first {
+            let request = McpRequest::subscribe_resource(Uuid::new_v4().to_string(), uri.to_string());
+            let response = connection.send_request(request).await.and_then(|r| r.into_result());
+            if let Err(e) = response {
+                self.resource_subscriptions.remove(uri);
+                return Err(e);
+            }
+        }
+        Ok(subscription)
+    }
 
-        Ok(resource_data)
+    /// Cancela a assinatura de um resource, encerrando seus streams
+    pub async fn unsubscribe_resource(&self, uri: &str) -> SageXResult<()> {
+        let _guard = self.subscription_lock.lock().await;
+        if !self.resource_subscriptions.remove(uri) {
+            return Ok(());
+        }
+        if let Some(connection) = self.mcp_connection().await {
+            let request = McpRequest::unsubscribe_resource(Uuid::new_v4().to_string(), uri.to_string());
+            connection.send_request(request).await?.into_result()?;
+        }
+        Ok(())
     }
 
-    /// Registra uma ferramenta MCP
+    /// Registra uma ferramenta MCP local
+    ///
+    /// Uma ferramenta do servidor com o mesmo nome é sobreposta.
     pub async fn register_tool(&self, tool: McpTool) -> SageXResult<()> {
-        let mut tools = self.available_tools.write().await;
+        let mut tools = self.local_tools.write().await;
         
         // Verificar se já existe
         if tools.iter().any(|t| t.name == tool.name) {
@@ -518,9 +904,11 @@ impl SageXClient {
         Ok(())
     }
 
-    /// Registra um resource MCP
+    /// Registra um resource MCP local
+    ///
+    /// Um resource do servidor com a mesma URI é sobreposto.
     pub async fn register_resource(&self, resource: McpResource) -> SageXResult<()> {
-        let mut resources = self.available_resources.write().await;
+        let mut resources = self.local_resources.write().await;
         
         // Verificar se já existe
         if resources.iter().any(|r| r.uri == resource.uri) {
@@ -551,8 +939,8 @@ impl SageXClient {
         }
 
         // Métricas de ferramentas e resources
-        let tools_count = self.available_tools.read().await.len();
-        let resources_count = self.available_resources.read().await.len();
+        let tools_count = self.list_tools().await.len();
+        let resources_count = self.list_resources().await.len();
         metrics.insert("available_tools_count".to_string(), Value::from(tools_count));
         metrics.insert("available_resources_count".to_string(), Value::from(resources_count));
 
@@ -578,17 +966,24 @@ impl SageXClient {
 
     /// Verifica a saúde da conexão
     pub async fn health_check(&self) -> SageXResult<bool> {
-        let config = self.config.read().await;
-        let url = format!("{}/health", config.api_base_url);
-        
-        let response = self.http_client
-            .get(&url)
-            .timeout(Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|e| SageXError::connection(format!("Health check falhou: {}", e)))?;
+        let (request, policy) = {
+            let config = self.config.read().await;
+            let request = self.http_client
+                .get(format!("{}/health", config.api_base_url))
+                .timeout(Duration::from_secs(10));
+            (request, self.retry_policy(&config))
+        };
+        match send_with_retry(&policy, request, "Health check").await {
+            Ok(_) => Ok(true),
+            // O servidor respondeu, mas não está saudável
+            Err(SageXError::Http(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 
-        Ok(response.status().is_success())
+    /// Política de retentativa das chamadas HTTP
+    fn retry_policy(&self, config: &SageXConfig) -> RetryPolicy {
+        self.retry.clone().unwrap_or_else(|| Backoff::from_network(&config.network).into())
     }
 
     /// Inicia o processamento de eventos em background
@@ -645,11 +1040,373 @@ impl SageXClient {
     }
 }
 
+/// Busca todas as páginas de uma operação `*/list` e substitui o catálogo
+async fn discover<R>(
+    connection: &McpConnection,
+    method: &str,
+    target: &RwLock<Vec<R::Item>>,
+) -> SageXResult<()>
+where
+    R: Paginated + DeserializeOwned,
+{
+    let items = fetch_all::<R>(connection, method).await?;
+    *target.write().await = items;
+    Ok(())
+}
+
+/// Busca todas as páginas de uma operação `*/list`
+///
+/// Servidores sem suporte ao método resultam em lista vazia.
+async fn fetch_all<R>(connection: &McpConnection, method: &str) -> SageXResult<Vec<R::Item>>
+where
+    R: Paginated + DeserializeOwned,
+{
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut seen = HashSet::new();
+
+    loop {
+        let request = McpRequest::new(Uuid::new_v4().to_string(), method.to_string(), None)
+            .with_cursor(cursor.clone());
+
+        let result = match connection.send_request(request).await?.into_result() {
+            Ok(result) => result,
+            Err(e) if e.as_mcp_error().is_some_and(|e| e.code == error_codes::METHOD_NOT_FOUND) => {
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let page: R = serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de {}: {}", method, e)))?;
+        let (page_items, next_cursor) = page.into_page();
+        items.extend(page_items);
+
+        // Um cursor já visitado indica ciclo na paginação do servidor
+        match next_cursor {
+            Some(next) if !seen.insert(next.clone()) => {
+                return Err(SageXError::mcp_protocol(format!("Cursor repetido em {}: {}", method, next)));
+            }
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(items)
+}
+
+/// Atualiza o catálogo quando o servidor notifica mudanças nas listas
+///
+/// Após uma reconexão, reassina os resources e recarrega todas as listas.
+/// Mantém apenas uma referência fraca: a task termina quando a conexão é
+/// descartada, mesmo sem `disconnect_mcp`.
+async fn watch_catalog(
+    connection: WeakConnection,
+    mut notifications: broadcast::Receiver<crate::mcp::McpNotification>,
+    mut events: broadcast::Receiver<ConnectionEvent>,
+    tools: Arc<RwLock<Vec<McpTool>>>,
+    resources: Arc<RwLock<Vec<McpResource>>>,
+    prompts: Arc<RwLock<Vec<McpPrompt>>>,
+    subscriptions: ResourceSubscriptions,
+) {
+    loop {
+        let received = tokio::select! {
+            notification = notifications.recv() => notification,
+            event = events.recv() => {
+                match event {
+                    Ok(ConnectionEvent::Reconnected { .. }) => {
+                        let Some(connection) = connection.upgrade() else {
+                            break;
+                        };
+                        resubscribe(&connection, &subscriptions).await;
+                        // A sessão é nova: o catálogo pode ter mudado durante a queda
+                        refresh_catalog(&connection, &tools, &resources, &prompts, (true, true, true)).await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                    _ => {}
+                }
+                continue;
+            }
+        };
+
+        let refresh = match received {
+            Ok(notification) => match notification.method.as_str() {
+                "notifications/resources/updated" => {
+                    match notification.params.map(serde_json::from_value::<ResourceUpdated>) {
+                        Some(Ok(update)) => {
+                            subscriptions.route(&update);
+                        }
+                        _ => log::warn!("notifications/resources/updated sem URI válida"),
+                    }
+                    continue;
+                }
+                "notifications/tools/list_changed" => (true, false, false),
+                "notifications/resources/list_changed" => (false, true, false),
+                "notifications/prompts/list_changed" => (false, false, true),
+                _ => continue,
+            },
+            // Notificações perdidas: atualizar tudo por segurança
+            Err(broadcast::error::RecvError::Lagged(_)) => (true, true, true),
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let Some(connection) = connection.upgrade() else {
+            break;
+        };
+        refresh_catalog(&connection, &tools, &resources, &prompts, refresh).await;
+    }
+}
+
+/// Reassina os resources registrados após uma reconexão
+async fn resubscribe(connection: &McpConnection, subscriptions: &ResourceSubscriptions) {
+    for uri in subscriptions.uris() {
+        let request = McpRequest::subscribe_resource(Uuid::new_v4().to_string(), uri.clone());
+        if let Err(e) = connection.send_request(request).await.and_then(|r| r.into_result()) {
+            log::warn!("Falha ao reassinar o resource {}: {}", uri, e);
+        }
+    }
+}
+
+/// Atualiza as listas de ferramentas, resources e prompts indicadas
+async fn refresh_catalog(
+    connection: &McpConnection,
+    tools: &Arc<RwLock<Vec<McpTool>>>,
+    resources: &Arc<RwLock<Vec<McpResource>>>,
+    prompts: &Arc<RwLock<Vec<McpPrompt>>>,
+    (refresh_tools, refresh_resources, refresh_prompts): (bool, bool, bool),
+) {
+    if refresh_tools {
+        if let Err(e) = discover::<ListToolsResult>(connection, "tools/list", tools).await {
+            log::warn!("Falha ao atualizar ferramentas MCP: {}", e);
+        }
+    }
+    if refresh_resources {
+        if let Err(e) = discover::<ListResourcesResult>(connection, "resources/list", resources).await {
+            log::warn!("Falha ao atualizar resources MCP: {}", e);
+        }
+    }
+    if refresh_prompts {
+        if let Err(e) = discover::<ListPromptsResult>(connection, "prompts/list", prompts).await {
+            log::warn!("Falha ao atualizar prompts MCP: {}", e);
+        }
+    }
+}
+
+/// Combina itens descobertos com registros locais, que têm precedência
+fn merge_overrides<T: Clone>(discovered: &[T], local: &[T], key: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut merged: Vec<T> = discovered
+        .iter()
+        .map(|item| local.iter().find(|l| key(l) == key(item)).unwrap_or(item).clone())
+        .collect();
+    merged.extend(
+        local
+            .iter()
+            .filter(|l| !discovered.iter().any(|d| key(d) == key(l)))
+            .cloned(),
+    );
+    merged
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mcp::{McpMessage, McpResponse, RequestId};
+    use crate::mcp::transport::MockTransport;
     use crate::models::SessionContext;
 
+    /// Simula um servidor MCP respondendo `initialize`, `tools/call`, `tools/list`
+    /// (duas páginas; a segunda com as ferramentas compartilhadas) e `resources/list`
+    fn spawn_tool_server(mock: MockTransport) -> Arc<std::sync::Mutex<Vec<String>>> {
+        let extra_tools = Arc::new(std::sync::Mutex::new(vec!["lint".to_string()]));
+        let server_tools = extra_tools.clone();
+        tokio::spawn(async move {
+            let mut answered = 0;
+            let mut flaky_failed = false;
+            loop {
+                let sent = mock.sent_messages().await;
+                for message in sent.iter().skip(answered) {
+                    let McpMessage::Request(request) = message else { continue };
+                    let tool = request.params.as_ref()
+                        .and_then(|p| p.get("name"))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    let response = match (request.method.as_str(), tool) {
+                        ("tools/call", "missing") => McpMessage::new_error_response(
+                            request.id.clone(),
+                            crate::models::McpError {
+                                code: -32602,
+                                message: "Ferramenta desconhecida".to_string(),
+                                data: None,
+                            },
+                        ),
+                        ("tools/call", "failing") => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "content": [{"type": "text", "text": "falhou"}],
+                                "isError": true
+                            }),
+                        ),
+                        ("tools/call", _) => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "content": [{"type": "text", "text": request.params.as_ref().unwrap()["arguments"]["input"]}]
+                            }),
+                        ),
+                        ("tools/list", _) => {
+                            let cursor = request.params.as_ref().and_then(|p| p.get("cursor"));
+                            let result = if cursor.is_none() {
+                                serde_json::json!({
+                                    "tools": [{"name": "echo", "description": "Eco", "inputSchema": {"type": "object"}}],
+                                    "nextCursor": "page-2"
+                                })
+                            } else {
+                                let tools: Vec<Value> = server_tools.lock().unwrap().iter()
+                                    .map(|name| serde_json::json!({"name": name, "inputSchema": {"type": "object"}}))
+                                    .collect();
+                                serde_json::json!({ "tools": tools })
+                            };
+                            McpMessage::new_success_response(request.id.clone(), result)
+                        }
+                        ("cycle/list", _) => {
+                            // Paginação defeituosa: a -> b -> a
+                            let next = match request.params.as_ref().and_then(|p| p["cursor"].as_str()) {
+                                Some("a") => "b",
+                                _ => "a",
+                            };
+                            McpMessage::new_success_response(
+                                request.id.clone(),
+                                serde_json::json!({ "tools": [], "nextCursor": next }),
+                            )
+                        }
+                        ("resources/list", _) => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "resources": [{"uri": "file:///README.md", "name": "README", "mimeType": "text/markdown"}]
+                            }),
+                        ),
+                        ("resources/read", _) => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "contents": [
+                                    {"uri": "file:///README.md", "mimeType": "text/markdown", "text": "# SAGE-X"},
+                                    {"uri": "file:///logo.png", "mimeType": "image/png", "blob": "iVBORw=="}
+                                ]
+                            }),
+                        ),
+                        ("resources/templates/list", _) => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "resourceTemplates": [{"uriTemplate": "file:///{+path}{?rev}", "name": "Arquivo"}]
+                            }),
+                        ),
+                        ("completion/complete", _) => {
+                            let prefix = request.params.as_ref().unwrap()["argument"]["value"].as_str().unwrap_or_default();
+                            let values: Vec<&str> = ["python", "pytorch", "rust", "ruby"]
+                                .into_iter()
+                                .filter(|value| value.starts_with(prefix))
+                                .collect();
+                            McpMessage::new_success_response(
+                                request.id.clone(),
+                                serde_json::json!({
+                                    "completion": {"values": values, "total": values.len(), "hasMore": false}
+                                }),
+                            )
+                        }
+                        ("prompts/list", _) => {
+                            let cursor = request.params.as_ref().and_then(|p| p.get("cursor"));
+                            let result = if cursor.is_none() {
+                                serde_json::json!({
+                                    "prompts": [{"name": "review", "arguments": [
+                                        {"name": "code", "required": true},
+                                        {"name": "style"}
+                                    ]}],
+                                    "nextCursor": "prompts-2"
+                                })
+                            } else {
+                                let prompts: Vec<Value> = server_tools.lock().unwrap().iter()
+                                    .map(|name| serde_json::json!({"name": format!("{}-prompt", name)}))
+                                    .collect();
+                                serde_json::json!({ "prompts": prompts })
+                            };
+                            McpMessage::new_success_response(request.id.clone(), result)
+                        }
+                        ("prompts/get", _) => {
+                            let arguments = &request.params.as_ref().unwrap()["arguments"];
+                            McpMessage::new_success_response(
+                                request.id.clone(),
+                                serde_json::json!({
+                                    "description": "Revisão de código",
+                                    "messages": [{
+                                        "role": "user",
+                                        "content": {"type": "text", "text": format!("Revise: {}", arguments["code"].as_str().unwrap_or_default())}
+                                    }]
+                                }),
+                            )
+                        }
+                        ("resources/subscribe", _)
+                            if !flaky_failed && request.params.as_ref().unwrap()["uri"] == "file:///flaky" =>
+                        {
+                            flaky_failed = true;
+                            McpMessage::new_error_response(
+                                request.id.clone(),
+                                crate::models::McpError {
+                                    code: -32603,
+                                    message: "Falha temporária".to_string(),
+                                    data: None,
+                                },
+                            )
+                        }
+                        ("initialize", _) => McpMessage::new_success_response(
+                            request.id.clone(),
+                            serde_json::json!({
+                                "protocolVersion": crate::mcp::MCP_VERSION,
+                                "capabilities": {"tools": {"listChanged": true}, "resources": {"subscribe": true}, "completions": {}},
+                                "serverInfo": {"name": "mock", "version": "0.0.0"}
+                            }),
+                        ),
+                        _ => McpMessage::new_success_response(request.id.clone(), serde_json::json!({})),
+                    };
+                    mock.add_incoming_message(response).await;
+                }
+                answered = sent.len();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        extra_tools
+    }
+
+    async fn connected_client() -> SageXClient {
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+
+        SageXClient::builder()
+            .with_transport(Box::new(mock))
+            .build()
+            .await
+            .unwrap()
+    }
+
+    /// Aguarda a resposta do cliente a um request do servidor
+    async fn wait_for_response(mock: &MockTransport, request_id: &str) -> McpResponse {
+        let id = RequestId::from(request_id);
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                for message in mock.sent_messages().await {
+                    if let McpMessage::Response(response) = message {
+                        if response.id.as_ref() == Some(&id) {
+                            return response;
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("resposta não enviada")
+    }
+
+    fn tool_names(tools: &[McpTool]) -> Vec<&str> {
+        tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
     #[tokio::test]
     async fn test_client_creation() {
         let client = SageXClient::new().await;
@@ -659,8 +1416,10 @@ mod tests {
     #[tokio::test]
     async fn test_session_lifecycle() {
         // Use config with localhost URL for testing
-        let mut config = SageXConfig::default();
-        config.api_base_url = "http://localhost:8080".to_string();
+        let config = SageXConfig {
+            api_base_url: "http://localhost:8080".to_string(),
+            ..Default::default()
+        };
         
         let client = SageXClient::builder()
             .with_config(config)
@@ -678,6 +1437,14 @@ mod tests {
             editor_config: HashMap::new(),
         };
 
+        // Diretório inválido não inicia sessão
+        let invalid = SessionContext {
+            working_directory: String::new(),
+            ..context.clone()
+        };
+        assert!(client.start_session(invalid).await.is_err());
+        assert!(client.current_session().await.is_none());
+        
         // Iniciar sessão (não faz chamadas de rede)
         let session_id = client.start_session(context).await.unwrap();
         assert!(client.current_session().await.is_some());
@@ -688,6 +1455,38 @@ mod tests {
         assert!(client.current_session().await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_config_not_locked_during_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let _health = server.mock("GET", "/health")
+            .with_status(503)
+            .with_header("retry-after", "1")
+            .create_async()
+            .await;
+        let config = SageXConfig {
+            api_base_url: server.url(),
+            ..Default::default()
+        };
+        let client = Arc::new(SageXClient::builder()
+            .with_config(config.clone())
+            .with_retry(Backoff::default().with_max_retries(1).into())
+            .disable_cache()
+            .build()
+            .await
+            .unwrap());
+        
+        let checking = client.clone();
+        let health = tokio::spawn(async move { checking.health_check().await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        
+        // O health check aguarda o Retry-After sem segurar a configuração
+        tokio::time::timeout(Duration::from_millis(200), client.update_config(config))
+            .await
+            .expect("update_config bloqueado pela retentativa")
+            .unwrap();
+        assert!(!health.await.unwrap().unwrap());
+    }
+    
     #[tokio::test]
     async fn test_tool_registration() {
         let client = SageXClient::new().await.unwrap();
@@ -716,5 +1515,380 @@ mod tests {
         assert!(metrics.contains_key("available_tools_count"));
         assert!(metrics.contains_key("available_resources_count"));
     }
-}
 
+    #[tokio::test]
+    async fn test_execute_tool_returns_content() {
+        let client = connected_client().await;
+
+        let result = client.execute_tool("echo", serde_json::json!({"input": "olá"})).await.unwrap();
+        assert!(!result.is_error);
+        assert_eq!(result.text(), "olá");
+
+        client.disconnect_mcp().await.unwrap();
+        assert!(client.mcp_connection().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_client_releases_connection() {
+        let client = connected_client().await;
+        let weak = client.mcp_connection().await.unwrap().downgrade();
+
+        // Sem disconnect_mcp: a task do catálogo não pode manter a conexão viva
+        drop(client);
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while weak.upgrade().is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("conexão não foi liberada");
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_reports_tool_failure() {
+        let client = connected_client().await;
+
+        let result = client.execute_tool("failing", serde_json::json!({})).await.unwrap();
+        assert!(result.is_error);
+        assert_eq!(result.text(), "falhou");
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_maps_protocol_error() {
+        let client = connected_client().await;
+
+        let error = client.execute_tool("missing", serde_json::json!({})).await.unwrap_err();
+        assert_eq!(error.as_mcp_error().map(|e| e.code), Some(-32602));
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_requires_connection() {
+        let client = SageXClient::new().await.unwrap();
+
+        let error = client.execute_tool("echo", serde_json::json!({})).await.unwrap_err();
+        assert!(matches!(error, SageXError::Connection { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_cursor_cycle_is_rejected() {
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+
+        let error = fetch_all::<ListToolsResult>(&connection, "cycle/list").await.unwrap_err();
+        assert!(error.to_string().contains("Cursor repetido"), "{}", error);
+        let pages = mock.sent_messages().await.iter()
+            .filter(|m| m.method() == Some("cycle/list"))
+            .count();
+        assert_eq!(pages, 3);
+    }
+
+    #[tokio::test]
+    async fn test_session_roots_announced_to_server() {
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let mut config = SageXConfig::default();
+        config.mcp.roots = vec!["/srv/shared".into()];
+        let client = SageXClient::builder()
+            .with_config(config)
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+
+        client.start_session(SessionContext {
+            working_directory: "/home/dev/sage-x".to_string(),
+            project_name: Some("sage-x".to_string()),
+            git_branch: None,
+            technologies: Vec::new(),
+            environment: HashMap::new(),
+            editor_config: HashMap::new(),
+        }).await.unwrap();
+
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new("r-1", "roots/list".to_string(), None))).await;
+        let response = wait_for_response(&mock, "r-1").await;
+        let uris: Vec<String> = response.result.unwrap()["roots"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|root| root["uri"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(uris, ["file:///home/dev/sage-x", "file:///srv/shared"]);
+
+        client.end_session().await.unwrap();
+        assert_eq!(client.roots().roots().len(), 1);
+
+        let sent = mock.sent_messages().await;
+        let McpMessage::Request(initialize) = &sent[0] else { panic!("initialize não enviado") };
+        assert_eq!(initialize.params.as_ref().unwrap()["capabilities"]["roots"]["listChanged"], true);
+        let changes = sent
+            .iter()
+            .filter(|m| matches!(m, McpMessage::Notification(n) if n.method == ROOTS_LIST_CHANGED))
+            .count();
+        assert_eq!(changes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_prompts_listed_validated_and_refreshed() {
+        let mock = MockTransport::new();
+        let server_names = spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+
+        let names: Vec<String> = client.list_prompts().await.into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["review", "lint-prompt"]);
+
+        let arguments = HashMap::from([("code".to_string(), "fn main() {}".to_string())]);
+        let prompt = client.get_prompt("review", arguments).await.unwrap();
+        assert_eq!(prompt.messages[0].role, crate::mcp::Role::User);
+        assert_eq!(prompt.messages[0].content.as_text(), Some("Revise: fn main() {}"));
+
+        let missing = client.get_prompt("review", HashMap::new()).await.unwrap_err();
+        assert!(matches!(missing, SageXError::Validation { ref field, .. } if field == "code"));
+        let unknown = HashMap::from([("code".to_string(), "x".to_string()), ("lang".to_string(), "rs".to_string())]);
+        assert!(client.get_prompt("review", unknown).await.is_err());
+        assert!(client.get_prompt("inexistente", HashMap::new()).await.is_err());
+
+        server_names.lock().unwrap().push("fmt".to_string());
+        mock.add_incoming_message(McpMessage::new_notification(
+            "notifications/prompts/list_changed".to_string(),
+            None,
+        )).await;
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.list_prompts().await.len() < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("prompts não atualizados");
+    }
+
+    #[tokio::test]
+    async fn test_read_resource_and_templates() {
+        let client = connected_client().await;
+
+        let resource = client.get_resource("file:///README.md").await.unwrap();
+        assert_eq!(resource.contents[0].text(), Some("# SAGE-X"));
+        assert_eq!(resource.contents[1].mime_type(), Some("image/png"));
+        assert!(resource.contents[1].text().is_none());
+        assert_eq!(resource.contents[1].to_bytes().unwrap(), [0x89, b'P', b'N', b'G']);
+
+        let templates = client.list_resource_templates().await.unwrap();
+        let variables = HashMap::from([("path".to_string(), "src/main.rs".to_string())]);
+        assert_eq!(templates[0].expand(&variables).unwrap(), "file:///src/main.rs");
+    }
+
+    #[tokio::test]
+    async fn test_resource_subscription_stream() {
+        use futures::StreamExt;
+
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+
+        let mut updates = client.subscribe_resource("file:///README.md").await.unwrap();
+        let _second = client.subscribe_resource("file:///README.md").await.unwrap();
+        mock.add_incoming_message(McpMessage::Notification(
+            crate::mcp::McpNotification::resource_updated("file:///README.md".to_string()),
+        )).await;
+
+        let update = tokio::time::timeout(Duration::from_secs(2), updates.next()).await.unwrap().unwrap();
+        assert_eq!(update.uri, "file:///README.md");
+
+        client.unsubscribe_resource("file:///README.md").await.unwrap();
+        assert!(updates.next().await.is_none());
+
+        let methods: Vec<String> = mock.sent_messages().await.iter()
+            .filter_map(|m| m.method().map(str::to_string))
+            .filter(|m| m.contains("subscribe"))
+            .collect();
+        assert_eq!(methods, ["resources/subscribe", "resources/unsubscribe"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_subscribe_keeps_waiting_subscriber() {
+        use futures::StreamExt;
+
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+
+        // O segundo assinante chega com o primeiro request em andamento
+        let (first, second) = tokio::join!(
+            client.subscribe_resource("file:///flaky"),
+            client.subscribe_resource("file:///flaky"),
+        );
+        assert!(first.is_err());
+        let mut updates = second.unwrap();
+        assert!(client.resource_subscriptions.contains("file:///flaky"));
+
+        mock.add_incoming_message(McpMessage::Notification(
+            crate::mcp::McpNotification::resource_updated("file:///flaky".to_string()),
+        )).await;
+        let update = tokio::time::timeout(Duration::from_secs(2), updates.next()).await.unwrap().unwrap();
+        assert_eq!(update.uri, "file:///flaky");
+
+        let subscribes = mock.sent_messages().await.iter()
+            .filter(|m| m.method() == Some("resources/subscribe"))
+            .count();
+        assert_eq!(subscribes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resubscribes_resources() {
+        use futures::StreamExt;
+
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_transport(Box::new(mock.clone()))
+            .with_reconnect(Backoff::default().with_delays(Duration::from_millis(10), Duration::from_millis(50)).into())
+            .build()
+            .await
+            .unwrap();
+        let mut updates = client.subscribe_resource("file:///README.md").await.unwrap();
+
+        // Derrubar o transporte dispara a reconexão
+        mock.clone().close().await.unwrap();
+
+        let count = |method: &'static str| {
+            let mock = mock.clone();
+            async move { mock.sent_messages().await.iter().filter(|m| m.method() == Some(method)).count() }
+        };
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while count("resources/subscribe").await < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("resource não reassinado");
+        assert_eq!(count("initialize").await, 2);
+        assert!(client.mcp_connection().await.unwrap().is_connected().await);
+
+        mock.add_incoming_message(McpMessage::Notification(
+            crate::mcp::McpNotification::resource_updated("file:///README.md".to_string()),
+        )).await;
+        let update = tokio::time::timeout(Duration::from_secs(2), updates.next()).await.unwrap().unwrap();
+        assert_eq!(update.uri, "file:///README.md");
+    }
+
+    #[tokio::test]
+    async fn test_completion_debounced_and_cached() {
+        use crate::mcp::CompletionReference;
+
+        let mock = MockTransport::new();
+        spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_completion_options(CompletionOptions::new().with_debounce(Duration::from_millis(40)))
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+        let params = |value: &str| CompleteParams::new(CompletionReference::prompt("review"), "language", value);
+
+        // Digitação rápida: apenas o último pedido chega ao servidor
+        let (p, py, pyt) = tokio::join!(
+            client.complete(params("p")),
+            client.complete(params("py")),
+            client.complete(params("pyt")),
+        );
+        assert!(matches!(p, Err(SageXError::Cancelled { .. })));
+        assert!(matches!(py, Err(SageXError::Cancelled { .. })));
+        let pyt = pyt.unwrap();
+        assert_eq!(pyt.values, ["python", "pytorch"]);
+        assert!(!pyt.has_more);
+
+        // Prefixo mais longo é filtrado do cache
+        assert_eq!(client.complete(params("pyth")).await.unwrap().values, ["python"]);
+        assert_eq!(client.complete(params("r")).await.unwrap().values, ["rust", "ruby"]);
+
+        let requests = mock.sent_messages().await.iter()
+            .filter(|m| m.method() == Some("completion/complete"))
+            .count();
+        assert_eq!(requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_builder_registers_request_handlers() {
+        use crate::mcp::StubSamplingHandler;
+
+        let client = SageXClient::builder()
+            .with_sampling(SamplingRequestHandler::new(StubSamplingHandler::new()))
+            .build()
+            .await
+            .unwrap();
+
+        assert!(client.request_handlers().contains("sampling/createMessage"));
+
+        let client = SageXClient::builder()
+            .with_elicitation(ElicitationRequestHandler::new(crate::mcp::ScriptedElicitationHandler::new()))
+            .build()
+            .await
+            .unwrap();
+        assert!(client.request_handlers().contains("elicitation/create"));
+    }
+
+    #[tokio::test]
+    async fn test_discovery_follows_pagination() {
+        let client = connected_client().await;
+
+        assert_eq!(tool_names(&client.list_tools().await), ["echo", "lint"]);
+
+        let resources = client.list_resources().await;
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].mime_type.as_deref(), Some("text/markdown"));
+
+        client.disconnect_mcp().await.unwrap();
+        assert!(client.list_tools().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_registration_overrides_discovered() {
+        let client = connected_client().await;
+
+        client.register_tool(McpTool {
+            name: "echo".to_string(),
+            description: "Eco local".to_string(),
+            input_schema: serde_json::json!({"type": "object"}),
+            metadata: None,
+        }).await.unwrap();
+
+        let tools = client.list_tools().await;
+        assert_eq!(tool_names(&tools), ["echo", "lint"]);
+        assert_eq!(tools[0].description, "Eco local");
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_changed_refreshes_catalog() {
+        let mock = MockTransport::new();
+        let server_tools = spawn_tool_server(mock.clone());
+        let client = SageXClient::builder()
+            .with_transport(Box::new(mock.clone()))
+            .build()
+            .await
+            .unwrap();
+
+        server_tools.lock().unwrap().push("fmt".to_string());
+        mock.add_incoming_message(McpMessage::new_notification(
+            "notifications/tools/list_changed".to_string(),
+            None,
+        )).await;
+
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while client.list_tools().await.len() != 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("catálogo não foi atualizado");
+
+        assert_eq!(tool_names(&client.list_tools().await), ["echo", "lint", "fmt"]);
+    }
+}
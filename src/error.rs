@@ -4,9 +4,13 @@
 //! na integração com MCP e sistema de regras.
 
 use std::fmt;
+use std::time::Duration;
 
 use thiserror::Error;
 
+use crate::mcp::messages::error_codes;
+use crate::models::McpError;
+
 /// Tipo de resultado padrão para a biblioteca
 pub type SageXResult<T> = Result<T, SageXError>;
 
@@ -57,6 +61,17 @@ pub enum SageXError {
         message: String 
     },
 
+    /// Erro retornado pelo servidor MCP (resposta JSON-RPC de erro)
+    #[error("Erro MCP {}: {}", .0.code, .0.message)]
+    McpRemote(McpError),
+
+    /// Operação cancelada antes de terminar
+    #[error("Operação cancelada: {operation}")]
+    Cancelled {
+        /// Operação cancelada
+        operation: String
+    },
+
     /// Erro de serialização/deserialização
     #[error("Erro de serialização: {message}")]
     Serialization { 
@@ -75,10 +90,10 @@ pub enum SageXError {
     Wasm { message: String },
 
     /// Erro de timeout
-    #[error("Timeout após {seconds}s: {operation}")]
+    #[error("Timeout após {millis}ms: {operation}")]
     Timeout { 
-        /// Tempo limite em segundos
-        seconds: u64, 
+        /// Tempo limite em milissegundos
+        millis: u64, 
         /// Operação que sofreu timeout
         operation: String 
     },
@@ -164,6 +179,41 @@ impl SageXError {
         }
     }
 
+    /// Cria um erro a partir de uma resposta de erro do servidor MCP
+    pub fn mcp_remote(error: McpError) -> Self {
+        Self::McpRemote(error)
+    }
+
+    /// Obtém o erro JSON-RPC do servidor, se houver
+    pub fn as_mcp_error(&self) -> Option<&McpError> {
+        match self {
+            Self::McpRemote(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Cria um erro de operação cancelada
+    pub fn cancelled<S: Into<String>>(operation: S) -> Self {
+        Self::Cancelled {
+            operation: operation.into(),
+        }
+    }
+
+    /// Converte em erro JSON-RPC para responder a um request do peer
+    pub fn to_mcp_error(&self) -> McpError {
+        let code = match self {
+            Self::McpRemote(error) => return error.clone(),
+            Self::Validation { .. } | Self::Serialization { .. } | Self::Json(_) => error_codes::INVALID_PARAMS,
+            _ => error_codes::INTERNAL_ERROR,
+        };
+
+        McpError {
+            code,
+            message: self.to_string(),
+            data: Some(serde_json::json!({ "errorCode": self.error_code() })),
+        }
+    }
+
     /// Cria um erro de serialização
     pub fn serialization<S: Into<String>>(message: S) -> Self {
         Self::Serialization {
@@ -171,10 +221,18 @@ impl SageXError {
         }
     }
 
-    /// Cria um erro de timeout
+    /// Cria um erro de timeout a partir de um limite em segundos
     pub fn timeout<S: Into<String>>(seconds: u64, operation: S) -> Self {
         Self::Timeout {
-            seconds,
+            millis: seconds.saturating_mul(1000),
+            operation: operation.into(),
+        }
+    }
+
+    /// Cria um erro de timeout preservando a precisão em milissegundos
+    pub fn timeout_after<S: Into<String>>(elapsed: Duration, operation: S) -> Self {
+        Self::Timeout {
+            millis: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
             operation: operation.into(),
         }
     }
@@ -210,6 +268,38 @@ impl SageXError {
         }
     }
 
+    /// Acrescenta contexto de diagnóstico à mensagem do erro
+    ///
+    /// Erros remotos (`McpRemote`) mantêm a mensagem do servidor.
+    pub fn with_context<S: AsRef<str>>(mut self, context: S) -> Self {
+        let detail = match &mut self {
+            Self::Authentication { message }
+            | Self::Connection { message }
+            | Self::RuleProcessing { message, .. }
+            | Self::Cache { message }
+            | Self::Configuration { message }
+            | Self::McpProtocol { message }
+            | Self::Serialization { message }
+            | Self::Validation { message, .. }
+            | Self::Unknown { message } => Some(message),
+            Self::Cancelled { operation } | Self::Timeout { operation, .. } => Some(operation),
+            Self::Io(message)
+            | Self::Http(message)
+            | Self::Json(message)
+            | Self::Jwt(message)
+            | Self::Internal(message) => Some(message),
+            #[cfg(feature = "python-bridge")]
+            Self::PythonBridge { message } => Some(message),
+            #[cfg(feature = "wasm-support")]
+            Self::Wasm { message } => Some(message),
+            Self::McpRemote(_) => None,
+        };
+        if let Some(detail) = detail {
+            detail.push_str(&format!(" ({})", context.as_ref()));
+        }
+        self
+    }
+
     /// Verifica se o erro é recuperável
     pub fn is_recoverable(&self) -> bool {
         match self {
@@ -220,7 +310,9 @@ impl SageXError {
             Self::Authentication { .. }
             | Self::Configuration { .. }
             | Self::Validation { .. }
-            | Self::Serialization { .. } => false,
+            | Self::Serialization { .. }
+            | Self::McpRemote(_)
+            | Self::Cancelled { .. } => false,
             Self::RuleProcessing { .. } | Self::McpProtocol { .. } => true,
             Self::Io(_) | Self::Json(_) | Self::Jwt(_) => false,
             Self::Internal(_) | Self::Unknown { .. } => false,
@@ -239,7 +331,7 @@ impl SageXError {
             Self::RuleProcessing { .. } => ErrorCategory::Rules,
             Self::Cache { .. } => ErrorCategory::Cache,
             Self::Configuration { .. } | Self::Validation { .. } => ErrorCategory::Configuration,
-            Self::McpProtocol { .. } => ErrorCategory::Protocol,
+            Self::McpProtocol { .. } | Self::McpRemote(_) | Self::Cancelled { .. } => ErrorCategory::Protocol,
             Self::Serialization { .. } | Self::Json(_) => ErrorCategory::Serialization,
             Self::Timeout { .. } => ErrorCategory::Timeout,
             Self::Io(_) => ErrorCategory::Io,
@@ -261,6 +353,8 @@ impl SageXError {
             Self::Cache { .. } => "CACHE_001",
             Self::Configuration { .. } => "CONFIG_001",
             Self::McpProtocol { .. } => "MCP_001",
+            Self::McpRemote(_) => "MCP_002",
+            Self::Cancelled { .. } => "MCP_003",
             Self::Serialization { .. } => "SERIAL_001",
             Self::Timeout { .. } => "TIMEOUT_001",
             Self::Validation { .. } => "VALID_001",
@@ -431,7 +525,13 @@ mod tests {
         assert!(matches!(rule_error, SageXError::RuleProcessing { .. }));
 
         let timeout_error = sage_error!(timeout: 30, "Busca de regras");
-        assert!(matches!(timeout_error, SageXError::Timeout { .. }));
+        assert!(matches!(timeout_error, SageXError::Timeout { millis: 30_000, .. }));
+    }
+
+    #[test]
+    fn test_timeout_keeps_millisecond_precision() {
+        let error = SageXError::timeout_after(Duration::from_millis(250), "Handshake WebSocket");
+        assert_eq!(error.to_string(), "Timeout após 250ms: Handshake WebSocket");
     }
 
     #[test]
@@ -456,5 +556,45 @@ mod tests {
         let non_recoverable = SageXError::authentication("test");
         assert!(!non_recoverable.is_recoverable());
     }
+
+    #[test]
+    fn test_error_context() {
+        let error = SageXError::connection("recusada").with_context("3 tentativas");
+        assert_eq!(error.to_string(), "Erro de conexão: recusada (3 tentativas)");
+        assert!(error.is_recoverable());
+
+        let remote = SageXError::mcp_remote(McpError {
+            code: -32601,
+            message: "Método desconhecido".to_string(),
+            data: None,
+        });
+        assert_eq!(remote.with_context("ignorado").to_string(), "Erro MCP -32601: Método desconhecido");
+    }
+
+    #[test]
+    fn test_mcp_remote_error() {
+        let error = SageXError::mcp_remote(McpError {
+            code: -32602,
+            message: "Parâmetros inválidos".to_string(),
+            data: None,
+        });
+        assert_eq!(error.category(), ErrorCategory::Protocol);
+        assert_eq!(error.error_code(), "MCP_002");
+        assert!(!error.is_recoverable());
+        assert_eq!(error.as_mcp_error().map(|e| e.code), Some(-32602));
+        assert_eq!(error.to_string(), "Erro MCP -32602: Parâmetros inválidos");
+        assert_eq!(error.to_mcp_error().code, -32602);
+    }
+
+    #[test]
+    fn test_to_mcp_error_mapping() {
+        let invalid = SageXError::validation("uri", "esquema não suportado").to_mcp_error();
+        assert_eq!(invalid.code, error_codes::INVALID_PARAMS);
+        assert_eq!(invalid.data, Some(serde_json::json!({"errorCode": "VALID_001"})));
+
+        let internal = SageXError::Internal("falha".to_string()).to_mcp_error();
+        assert_eq!(internal.code, error_codes::INTERNAL_ERROR);
+        assert_eq!(internal.message, "Erro interno: falha");
+    }
 }
 
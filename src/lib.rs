@@ -69,12 +69,15 @@ pub mod mcp;
 // pub mod rules;
 // pub mod sync;
 pub mod models;
+pub mod retry;
 
 // Re-exportações públicas principais
 pub use client::{SageXClient, SageXClientBuilder, SageXEvent};
 pub use error::{SageXError, SageXResult};
+pub use retry::{Backoff, RetryPolicy};
+pub use mcp::{McpCapabilities, McpConnection, McpRequest, McpResponse};
 pub use models::{
-    SageXConfig, SessionContext, McpRequest, McpResponse, McpTool, McpResource,
+    SageXConfig, SessionContext, McpTool, McpResource,
     SageXRule, DevSession, ExecutionResult,
 };
 
@@ -104,16 +107,18 @@ pub const USER_AGENT: &str = concat!(
 
 /// Configuração padrão para desenvolvimento
 pub fn default_dev_config() -> SageXConfig {
-    let mut config = SageXConfig::default();
-    config.api_base_url = "http://localhost:8001".to_string();
-    config
+    SageXConfig {
+        api_base_url: "http://localhost:8001".to_string(),
+        ..Default::default()
+    }
 }
 
 /// Configuração padrão para produção
 pub fn default_prod_config() -> SageXConfig {
-    let mut config = SageXConfig::default();
-    config.api_base_url = "https://api.sage-x.ai".to_string();
-    config
+    SageXConfig {
+        api_base_url: "https://api.sage-x.ai".to_string(),
+        ..Default::default()
+    }
 }
 
 /// Utilitário para logging configurado
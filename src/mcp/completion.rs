@@ -0,0 +1,269 @@
+//! Completação de argumentos com debounce e cache
+//!
+//! Editores pedem sugestões a cada tecla. O `Completer` espera um intervalo
+//! curto antes de consultar o servidor, descartando pedidos superados por um
+//! mais recente, e reaproveita respostas recentes, inclusive filtrando as de
+//! um prefixo mais curto quando o servidor já retornou todas as opções.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::error::{SageXError, SageXResult};
+use super::protocol::McpConnection;
+use super::types::{CompleteParams, Completion, CompletionContext, CompletionReference};
+
+/// Intervalo padrão de espera antes de consultar o servidor
+pub const DEFAULT_COMPLETION_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Validade padrão das respostas em cache
+pub const DEFAULT_COMPLETION_TTL: Duration = Duration::from_secs(30);
+
+/// Opções do `Completer`
+#[derive(Debug, Clone)]
+pub struct CompletionOptions {
+    /// Espera antes de consultar o servidor; zero desabilita o debounce
+    pub debounce: Duration,
+
+    /// Validade das respostas em cache
+    pub ttl: Duration,
+
+    /// Máximo de respostas mantidas em cache
+    pub max_entries: usize,
+}
+
+impl Default for CompletionOptions {
+    fn default() -> Self {
+        Self {
+            debounce: DEFAULT_COMPLETION_DEBOUNCE,
+            ttl: DEFAULT_COMPLETION_TTL,
+            max_entries: 256,
+        }
+    }
+}
+
+impl CompletionOptions {
+    /// Cria opções com os valores padrão
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a espera antes de consultar o servidor
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Define a validade das respostas em cache
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Define o máximo de respostas em cache
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+}
+
+/// Argumento completado: mesmo alvo, nome e contexto, qualquer valor parcial
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Scope {
+    reference: CompletionReference,
+    argument: String,
+    context: Option<CompletionContext>,
+}
+
+impl Scope {
+    fn of(params: &CompleteParams) -> Self {
+        Self {
+            reference: params.reference.clone(),
+            argument: params.argument.name.clone(),
+            context: params.context.clone(),
+        }
+    }
+}
+
+/// Resposta em cache para um valor parcial
+#[derive(Debug)]
+struct CachedCompletion {
+    value: String,
+    completion: Completion,
+    stored_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct CompleterState {
+    /// Último pedido de cada argumento, para descartar os superados
+    generations: HashMap<Scope, u64>,
+
+    /// Respostas recentes por argumento
+    entries: HashMap<Scope, Vec<CachedCompletion>>,
+}
+
+/// Camada de debounce e cache sobre `McpConnection::complete`
+///
+/// Clones compartilham o mesmo cache.
+#[derive(Debug, Clone, Default)]
+pub struct Completer {
+    options: CompletionOptions,
+    state: Arc<Mutex<CompleterState>>,
+}
+
+impl Completer {
+    /// Cria um completer com as opções informadas
+    pub fn new(options: CompletionOptions) -> Self {
+        Self {
+            options,
+            state: Arc::default(),
+        }
+    }
+
+    /// Opções em uso
+    pub fn options(&self) -> &CompletionOptions {
+        &self.options
+    }
+
+    /// Obtém sugestões para o valor parcial
+    ///
+    /// Respostas em cache retornam imediatamente. Caso contrário, aguarda o
+    /// debounce; se outro pedido para o mesmo argumento chegar nesse meio
+    /// tempo, este termina com `SageXError::Cancelled`.
+    pub async fn complete(&self, connection: &McpConnection, params: CompleteParams) -> SageXResult<Completion> {
+        let scope = Scope::of(&params);
+        let value = params.argument.value.clone();
+
+        let generation = {
+            let mut state = self.lock();
+            let generation = state.generations.entry(scope.clone()).or_default();
+            *generation += 1;
+            let generation = *generation;
+
+            if let Some(completion) = self.cached(&mut state, &scope, &value) {
+                return Ok(completion);
+            }
+            generation
+        };
+
+        if !self.options.debounce.is_zero() {
+            tokio::time::sleep(self.options.debounce).await;
+            if self.lock().generations.get(&scope) != Some(&generation) {
+                return Err(SageXError::cancelled("completion/complete"));
+            }
+        }
+
+        let completion = connection.complete(&params).await?;
+        self.store(scope, value, completion.clone());
+        Ok(completion)
+    }
+
+    /// Descarta todas as respostas em cache
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Procura a resposta exata ou filtra uma resposta completa de prefixo mais curto
+    fn cached(&self, state: &mut CompleterState, scope: &Scope, value: &str) -> Option<Completion> {
+        let ttl = self.options.ttl;
+        let entries = state.entries.get_mut(scope)?;
+        entries.retain(|entry| entry.stored_at.elapsed() < ttl);
+
+        if let Some(entry) = entries.iter().find(|entry| entry.value == value) {
+            return Some(entry.completion.clone());
+        }
+
+        let lowered = value.to_lowercase();
+        let entry = entries
+            .iter()
+            .filter(|entry| !entry.completion.has_more && lowered.starts_with(&entry.value.to_lowercase()))
+            .max_by_key(|entry| entry.value.len())?;
+        let values: Vec<String> = entry
+            .completion
+            .values
+            .iter()
+            .filter(|candidate| candidate.to_lowercase().starts_with(&lowered))
+            .cloned()
+            .collect();
+
+        Some(Completion {
+            total: Some(values.len() as u64),
+            values,
+            has_more: false,
+        })
+    }
+
+    fn store(&self, scope: Scope, value: String, completion: Completion) {
+        let mut state = self.lock();
+        state.entries.entry(scope).or_default().push(CachedCompletion {
+            value,
+            completion,
+            stored_at: Instant::now(),
+        });
+
+        // Remove as respostas mais antigas acima do limite
+        let mut total: usize = state.entries.values().map(Vec::len).sum();
+        while total > self.options.max_entries {
+            let oldest = state
+                .entries
+                .iter()
+                .filter_map(|(scope, entries)| entries.first().map(|entry| (scope.clone(), entry.stored_at)))
+                .min_by_key(|(_, stored_at)| *stored_at)
+                .map(|(scope, _)| scope);
+            let Some(oldest) = oldest else { break };
+            if let Some(entries) = state.entries.get_mut(&oldest) {
+                entries.remove(0);
+                if entries.is_empty() {
+                    state.entries.remove(&oldest);
+                }
+            }
+            total -= 1;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CompleterState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: &str) -> CompleteParams {
+        CompleteParams::new(CompletionReference::prompt("code_review"), "language", value)
+    }
+
+    fn completion(values: &[&str], has_more: bool) -> Completion {
+        Completion {
+            values: values.iter().map(|v| v.to_string()).collect(),
+            total: Some(values.len() as u64),
+            has_more,
+        }
+    }
+
+    #[test]
+    fn test_cache_reuses_complete_shorter_prefix() {
+        let completer = Completer::new(CompletionOptions::new().with_max_entries(2));
+        let scope = Scope::of(&params("py"));
+        completer.store(scope.clone(), "py".to_string(), completion(&["python", "pytorch", "PyPy"], false));
+
+        let mut state = completer.lock();
+        assert_eq!(completer.cached(&mut state, &scope, "py").unwrap().values.len(), 3);
+        assert_eq!(completer.cached(&mut state, &scope, "pyt").unwrap().values, ["python", "pytorch"]);
+        assert_eq!(completer.cached(&mut state, &scope, "pyp").unwrap().values, ["PyPy"]);
+        assert!(completer.cached(&mut state, &scope, "r").is_none());
+
+        // Outro contexto é outro argumento
+        let other = Scope::of(&params("py").with_context([("framework".to_string(), "django".to_string())].into()));
+        assert!(completer.cached(&mut state, &other, "py").is_none());
+        drop(state);
+
+        // Respostas parciais não servem para prefixos mais longos
+        completer.store(scope.clone(), "r".to_string(), completion(&["rust"], true));
+        completer.store(scope.clone(), "ru".to_string(), completion(&["ruby"], false));
+        let mut state = completer.lock();
+        assert!(completer.cached(&mut state, &scope, "rus").is_some());
+        assert!(completer.cached(&mut state, &scope, "py").is_none(), "entrada mais antiga removida pelo limite");
+    }
+}
@@ -0,0 +1,435 @@
+//! Elicitation: servidores pedindo dados estruturados ao usuário
+//!
+//! O servidor envia `elicitation/create` com uma mensagem e um schema JSON
+//! restrito a propriedades primitivas. A aplicação coleta a resposta
+//! implementando `ElicitationHandler`; o conteúdo aceito é validado contra o
+//! schema antes de ser devolvido.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{SageXError, SageXResult};
+use super::handlers::HandlerRegistry;
+
+/// Método JSON-RPC de elicitation
+pub const ELICITATION_METHOD: &str = "elicitation/create";
+
+/// Schema de uma propriedade primitiva
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PrimitiveSchema {
+    /// Texto, opcionalmente restrito a um formato ou a valores enumerados
+    String {
+        /// Rótulo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Descrição
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Tamanho mínimo
+        #[serde(rename = "minLength", default, skip_serializing_if = "Option::is_none")]
+        min_length: Option<usize>,
+        /// Tamanho máximo
+        #[serde(rename = "maxLength", default, skip_serializing_if = "Option::is_none")]
+        max_length: Option<usize>,
+        /// Formato (`email`, `uri`, `date`, `date-time`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        /// Valores aceitos
+        #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+        enum_values: Option<Vec<String>>,
+        /// Rótulos dos valores aceitos
+        #[serde(rename = "enumNames", default, skip_serializing_if = "Option::is_none")]
+        enum_names: Option<Vec<String>>,
+    },
+
+    /// Número decimal
+    Number {
+        /// Rótulo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Descrição
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Valor mínimo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum: Option<f64>,
+        /// Valor máximo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        maximum: Option<f64>,
+    },
+
+    /// Número inteiro
+    Integer {
+        /// Rótulo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Descrição
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Valor mínimo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum: Option<f64>,
+        /// Valor máximo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        maximum: Option<f64>,
+    },
+
+    /// Verdadeiro ou falso
+    Boolean {
+        /// Rótulo
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Descrição
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Valor sugerido
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<bool>,
+    },
+}
+
+impl PrimitiveSchema {
+    /// Valida um valor contra o schema
+    fn validate(&self, field: &str, value: &Value) -> SageXResult<()> {
+        let invalid = |message: String| Err(SageXError::validation(field, message));
+
+        match self {
+            Self::String { min_length, max_length, format, enum_values, .. } => {
+                let Some(text) = value.as_str() else {
+                    return invalid("esperado texto".to_string());
+                };
+                let length = text.chars().count();
+                if min_length.is_some_and(|min| length < min) || max_length.is_some_and(|max| length > max) {
+                    return invalid(format!("tamanho {} fora dos limites", length));
+                }
+                if let Some(allowed) = enum_values {
+                    if !allowed.iter().any(|v| v == text) {
+                        return invalid(format!("valor fora das opções: {}", allowed.join(", ")));
+                    }
+                }
+                if let Some(format) = format {
+                    if !matches_format(format, text) {
+                        return invalid(format!("formato {} inválido", format));
+                    }
+                }
+                Ok(())
+            }
+            Self::Number { minimum, maximum, .. } | Self::Integer { minimum, maximum, .. } => {
+                let number = match (self, value) {
+                    (Self::Integer { .. }, Value::Number(n)) if n.is_i64() || n.is_u64() => n.as_f64(),
+                    (Self::Integer { .. }, _) => None,
+                    (_, value) => value.as_f64(),
+                };
+                let Some(number) = number else {
+                    return invalid("esperado número".to_string());
+                };
+                if minimum.is_some_and(|min| number < min) || maximum.is_some_and(|max| number > max) {
+                    return invalid(format!("{} fora dos limites", number));
+                }
+                Ok(())
+            }
+            Self::Boolean { .. } => match value {
+                Value::Bool(_) => Ok(()),
+                _ => invalid("esperado booleano".to_string()),
+            },
+        }
+    }
+}
+
+/// Verifica os formatos de texto suportados pelo protocolo
+fn matches_format(format: &str, text: &str) -> bool {
+    match format {
+        "email" => text
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.') && !domain.contains('@')),
+        "uri" => reqwest::Url::parse(text).is_ok(),
+        "date" => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok(),
+        "date-time" => chrono::DateTime::parse_from_rfc3339(text).is_ok(),
+        // Formatos desconhecidos não são validados
+        _ => true,
+    }
+}
+
+/// Schema pedido pelo servidor: objeto plano de propriedades primitivas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitationSchema {
+    /// Sempre `object`
+    #[serde(rename = "type")]
+    pub schema_type: String,
+
+    /// Propriedades pedidas
+    #[serde(default)]
+    pub properties: BTreeMap<String, PrimitiveSchema>,
+
+    /// Propriedades obrigatórias
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
+}
+
+impl ElicitationSchema {
+    /// Valida o conteúdo aceito pelo usuário
+    pub fn validate(&self, content: &Map<String, Value>) -> SageXResult<()> {
+        if self.schema_type != "object" {
+            return Err(SageXError::validation("requestedSchema", "Schema de elicitation deve ser um objeto"));
+        }
+        if let Some(missing) = self.required.iter().find(|name| !content.contains_key(*name)) {
+            return Err(SageXError::validation(missing.clone(), "Campo obrigatório ausente"));
+        }
+        for (name, value) in content {
+            let schema = self
+                .properties
+                .get(name)
+                .ok_or_else(|| SageXError::validation(name.clone(), "Campo não pedido pelo servidor"))?;
+            schema.validate(name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parâmetros de `elicitation/create`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitRequest {
+    /// Mensagem exibida ao usuário
+    pub message: String,
+
+    /// Formato da resposta esperada
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: ElicitationSchema,
+}
+
+/// Ação tomada pelo usuário
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// Enviou os dados
+    Accept,
+    /// Recusou explicitamente
+    Decline,
+    /// Fechou sem decidir
+    Cancel,
+}
+
+/// Resultado de `elicitation/create`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElicitResult {
+    /// Ação tomada
+    pub action: ElicitAction,
+
+    /// Dados enviados, apenas quando aceito
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Map<String, Value>>,
+}
+
+/// Resposta do usuário a um pedido de elicitation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElicitationResponse {
+    /// Dados preenchidos
+    Accept(Map<String, Value>),
+    /// Recusa explícita
+    Decline,
+    /// Diálogo fechado sem decisão
+    Cancel,
+}
+
+impl From<ElicitationResponse> for ElicitResult {
+    fn from(response: ElicitationResponse) -> Self {
+        match response {
+            ElicitationResponse::Accept(content) => Self {
+                action: ElicitAction::Accept,
+                content: Some(content),
+            },
+            ElicitationResponse::Decline => Self {
+                action: ElicitAction::Decline,
+                content: None,
+            },
+            ElicitationResponse::Cancel => Self {
+                action: ElicitAction::Cancel,
+                content: None,
+            },
+        }
+    }
+}
+
+/// Coleta a resposta do usuário aos pedidos do servidor
+#[async_trait]
+pub trait ElicitationHandler: Send + Sync {
+    /// Apresenta a mensagem e coleta os dados pedidos
+    async fn elicit(&self, request: ElicitRequest) -> SageXResult<ElicitationResponse>;
+}
+
+/// Adapta um `ElicitationHandler` ao registro de handlers
+#[derive(Clone)]
+pub struct ElicitationRequestHandler {
+    handler: Arc<dyn ElicitationHandler>,
+}
+
+impl ElicitationRequestHandler {
+    /// Cria o adaptador
+    pub fn new<H: ElicitationHandler + 'static>(handler: H) -> Self {
+        Self {
+            handler: Arc::new(handler),
+        }
+    }
+
+    /// Registra em `elicitation/create`
+    ///
+    /// A capacidade `elicitation` passa a ser anunciada no `initialize`.
+    pub fn register(self, registry: &HandlerRegistry) {
+        registry.register_fn(ELICITATION_METHOD, move |request: ElicitRequest, _| {
+            let adapter = self.clone();
+            async move { adapter.process(request).await }
+        });
+    }
+
+    /// Coleta a resposta e valida o conteúdo aceito contra o schema
+    pub async fn process(&self, request: ElicitRequest) -> SageXResult<ElicitResult> {
+        let schema = request.requested_schema.clone();
+        let response = self.handler.elicit(request).await?;
+        if let ElicitationResponse::Accept(content) = &response {
+            schema.validate(content)?;
+        }
+        Ok(response.into())
+    }
+}
+
+impl fmt::Debug for ElicitationRequestHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElicitationRequestHandler").finish_non_exhaustive()
+    }
+}
+
+/// Handler com respostas roteirizadas, para testes
+///
+/// Responde na ordem em que as respostas foram enfileiradas e registra os
+/// pedidos recebidos; com a fila vazia, cancela.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedElicitationHandler {
+    responses: Arc<Mutex<VecDeque<ElicitationResponse>>>,
+    requests: Arc<Mutex<Vec<ElicitRequest>>>,
+}
+
+impl ScriptedElicitationHandler {
+    /// Cria um handler sem respostas
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enfileira uma resposta aceitando o conteúdo (um objeto JSON)
+    pub fn accept(self, content: Value) -> Self {
+        let content = match content {
+            Value::Object(content) => content,
+            other => panic!("conteúdo de elicitation deve ser um objeto: {}", other),
+        };
+        self.respond(ElicitationResponse::Accept(content))
+    }
+
+    /// Enfileira uma recusa
+    pub fn decline(self) -> Self {
+        self.respond(ElicitationResponse::Decline)
+    }
+
+    /// Enfileira um cancelamento
+    pub fn cancel(self) -> Self {
+        self.respond(ElicitationResponse::Cancel)
+    }
+
+    /// Enfileira uma resposta qualquer
+    pub fn respond(self, response: ElicitationResponse) -> Self {
+        self.responses.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push_back(response);
+        self
+    }
+
+    /// Pedidos recebidos até agora
+    pub fn requests(&self) -> Vec<ElicitRequest> {
+        self.requests.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+#[async_trait]
+impl ElicitationHandler for ScriptedElicitationHandler {
+    async fn elicit(&self, request: ElicitRequest) -> SageXResult<ElicitationResponse> {
+        self.requests.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(request);
+        let response = self.responses.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop_front();
+        Ok(response.unwrap_or(ElicitationResponse::Cancel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> ElicitRequest {
+        serde_json::from_value(serde_json::json!({
+            "message": "Informe seus dados de contato",
+            "requestedSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "minLength": 2},
+                    "email": {"type": "string", "format": "email"},
+                    "role": {"type": "string", "enum": ["dev", "ops"], "enumNames": ["Dev", "Ops"]},
+                    "age": {"type": "integer", "minimum": 18},
+                    "subscribe": {"type": "boolean", "default": false}
+                },
+                "required": ["name", "email"]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn content(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_schema_validation() {
+        let schema = request().requested_schema;
+
+        let valid = content(serde_json::json!({"name": "Ana", "email": "ana@sage.dev", "role": "dev", "age": 30}));
+        assert!(schema.validate(&valid).is_ok());
+
+        let invalid = [
+            (serde_json::json!({"name": "Ana"}), "email"),
+            (serde_json::json!({"name": "A", "email": "ana@sage.dev"}), "name"),
+            (serde_json::json!({"name": "Ana", "email": "ana"}), "email"),
+            (serde_json::json!({"name": "Ana", "email": "ana@sage.dev", "role": "qa"}), "role"),
+            (serde_json::json!({"name": "Ana", "email": "ana@sage.dev", "age": 30.5}), "age"),
+            (serde_json::json!({"name": "Ana", "email": "ana@sage.dev", "subscribe": "sim"}), "subscribe"),
+            (serde_json::json!({"name": "Ana", "email": "ana@sage.dev", "extra": 1}), "extra"),
+        ];
+        for (value, expected) in invalid {
+            match schema.validate(&content(value)) {
+                Err(SageXError::Validation { field, .. }) => assert_eq!(field, expected),
+                other => panic!("esperado erro em {}: {:?}", expected, other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scripted_handler_outcomes() {
+        let scripted = ScriptedElicitationHandler::new()
+            .accept(serde_json::json!({"name": "Ana", "email": "ana@sage.dev"}))
+            .accept(serde_json::json!({"name": "Ana"}))
+            .decline();
+        let handler = ElicitationRequestHandler::new(scripted.clone());
+
+        let accepted = handler.process(request()).await.unwrap();
+        assert_eq!(accepted.action, ElicitAction::Accept);
+        assert_eq!(accepted.content.unwrap()["name"], "Ana");
+
+        assert!(handler.process(request()).await.is_err());
+
+        let declined = serde_json::to_value(handler.process(request()).await.unwrap()).unwrap();
+        assert_eq!(declined, serde_json::json!({"action": "decline"}));
+
+        // Fila vazia: cancela
+        assert_eq!(handler.process(request()).await.unwrap().action, ElicitAction::Cancel);
+        assert_eq!(scripted.requests().len(), 4);
+        assert_eq!(scripted.requests()[0].message, "Informe seus dados de contato");
+    }
+}
@@ -0,0 +1,221 @@
+//! Registro de handlers para requests iniciados pelo servidor
+//!
+//! Aplicações registram handlers assíncronos por método (`sampling/createMessage`,
+//! `roots/list`, `elicitation/create`, ...). Cada request recebido é processado
+//! em sua própria task, sem bloquear a leitura da conexão.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::SageXResult;
+use crate::models::McpError;
+use super::messages::{error_codes, McpNotification, ProgressToken, RequestId};
+use super::protocol::McpConnection;
+
+/// Contexto de um request recebido do servidor
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// ID do request
+    pub request_id: RequestId,
+
+    /// Método chamado
+    pub method: String,
+
+    /// Cancelado quando o servidor envia `notifications/cancelled` ou a conexão cai
+    pub cancellation: CancellationToken,
+
+    /// Token de progresso informado pelo servidor, se houver
+    pub progress_token: Option<ProgressToken>,
+
+    /// Conexão que recebeu o request
+    pub connection: McpConnection,
+}
+
+impl RequestContext {
+    /// Envia uma notificação de progresso ao servidor, se ele pediu acompanhamento
+    pub async fn report_progress(&self, progress: f64, total: Option<f64>, message: Option<String>) -> SageXResult<()> {
+        let Some(token) = self.progress_token.clone() else {
+            return Ok(());
+        };
+        self.connection
+            .send_notification(McpNotification::progress(token, progress, total, message))
+            .await
+    }
+}
+
+/// Handler de um método iniciado pelo servidor
+#[async_trait]
+pub trait RequestHandler: Send + Sync {
+    /// Processa o request e produz o `result` ou o erro JSON-RPC
+    async fn handle(
+        &self,
+        params: Option<serde_json::Value>,
+        context: RequestContext,
+    ) -> Result<serde_json::Value, McpError>;
+}
+
+/// Adapta uma função assíncrona com parâmetros e resultado tipados
+struct TypedHandler<P, R, F> {
+    handler: F,
+    _types: PhantomData<fn(P) -> R>,
+}
+
+#[async_trait]
+impl<P, R, F, Fut> RequestHandler for TypedHandler<P, R, F>
+where
+    P: DeserializeOwned + Send + 'static,
+    R: Serialize + Send + 'static,
+    F: Fn(P, RequestContext) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = SageXResult<R>> + Send + 'static,
+{
+    async fn handle(
+        &self,
+        params: Option<serde_json::Value>,
+        context: RequestContext,
+    ) -> Result<serde_json::Value, McpError> {
+        // Requests sem params são tratados como objeto vazio
+        let params = params.unwrap_or_else(|| serde_json::json!({}));
+        let params: P = serde_json::from_value(params).map_err(|e| McpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Parâmetros inválidos para {}: {}", context.method, e),
+            data: None,
+        })?;
+
+        let result = (self.handler)(params, context).await.map_err(|e| e.to_mcp_error())?;
+
+        serde_json::to_value(result).map_err(|e| McpError {
+            code: error_codes::INTERNAL_ERROR,
+            message: format!("Falha ao serializar resultado: {}", e),
+            data: None,
+        })
+    }
+}
+
+/// Registro de handlers por método
+///
+/// Clones compartilham os mesmos handlers, o que permite registrar antes de
+/// conectar e manter os registros entre reconexões.
+#[derive(Clone, Default)]
+pub struct HandlerRegistry {
+    /// Handlers por método; lock síncrono pois é mantido apenas para clonar o `Arc`
+    handlers: Arc<RwLock<HashMap<String, Arc<dyn RequestHandler>>>>,
+}
+
+impl HandlerRegistry {
+    /// Cria um registro vazio
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra um handler, retornando o anterior para o mesmo método
+    pub fn register<S, H>(&self, method: S, handler: H) -> Option<Arc<dyn RequestHandler>>
+    where
+        S: Into<String>,
+        H: RequestHandler + 'static,
+    {
+        self.register_arc(method, Arc::new(handler))
+    }
+
+    /// Registra um handler compartilhado
+    pub fn register_arc<S: Into<String>>(
+        &self,
+        method: S,
+        handler: Arc<dyn RequestHandler>,
+    ) -> Option<Arc<dyn RequestHandler>> {
+        self.write().insert(method.into(), handler)
+    }
+
+    /// Registra uma função assíncrona com parâmetros e resultado tipados
+    ///
+    /// Falhas de deserialização viram `INVALID_PARAMS`; erros retornados pela
+    /// função são convertidos com `SageXError::to_mcp_error`.
+    pub fn register_fn<S, P, R, F, Fut>(&self, method: S, handler: F) -> Option<Arc<dyn RequestHandler>>
+    where
+        S: Into<String>,
+        P: DeserializeOwned + Send + 'static,
+        R: Serialize + Send + 'static,
+        F: Fn(P, RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SageXResult<R>> + Send + 'static,
+    {
+        self.register(method, TypedHandler {
+            handler,
+            _types: PhantomData,
+        })
+    }
+
+    /// Remove o handler de um método
+    pub fn unregister(&self, method: &str) -> Option<Arc<dyn RequestHandler>> {
+        self.write().remove(method)
+    }
+
+    /// Obtém o handler de um método
+    pub fn get(&self, method: &str) -> Option<Arc<dyn RequestHandler>> {
+        self.read().get(method).cloned()
+    }
+
+    /// Verifica se há handler para o método
+    pub fn contains(&self, method: &str) -> bool {
+        self.read().contains_key(method)
+    }
+
+    /// Lista os métodos registrados
+    pub fn methods(&self) -> Vec<String> {
+        let mut methods: Vec<String> = self.read().keys().cloned().collect();
+        methods.sort();
+        methods
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Arc<dyn RequestHandler>>> {
+        self.handlers.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Arc<dyn RequestHandler>>> {
+        self.handlers.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for HandlerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandlerRegistry")
+            .field("methods", &self.methods())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    #[async_trait]
+    impl RequestHandler for Fixed {
+        async fn handle(&self, _: Option<serde_json::Value>, _: RequestContext) -> Result<serde_json::Value, McpError> {
+            Ok(serde_json::json!(1))
+        }
+    }
+
+    #[test]
+    fn test_registry_register_and_replace() {
+        let registry = HandlerRegistry::new();
+        assert!(registry.register("roots/list", Fixed).is_none());
+        assert!(registry.register("roots/list", Fixed).is_some());
+        registry.register_fn("ping", |_: serde_json::Value, _| async { Ok(serde_json::json!({})) });
+
+        // Clones compartilham os registros
+        let shared = registry.clone();
+        assert_eq!(shared.methods(), ["ping", "roots/list"]);
+
+        assert!(registry.unregister("roots/list").is_some());
+        assert!(!shared.contains("roots/list"));
+        assert!(format!("{:?}", shared).contains("ping"));
+    }
+}
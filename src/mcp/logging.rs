@@ -0,0 +1,80 @@
+//! Logs estruturados enviados pelo servidor
+//!
+//! O servidor emite `notifications/message` a partir do nível definido em
+//! `logging/setLevel`. Os registros podem ser consumidos como stream ou
+//! repassados ao crate `log`, usando o nome do servidor como target.
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use super::messages::{LogLevel, McpNotification};
+
+/// Método da notificação de log
+pub const LOG_MESSAGE_NOTIFICATION: &str = "notifications/message";
+
+/// Registro de log recebido do servidor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// Severidade
+    pub level: LogLevel,
+
+    /// Componente que gerou o registro
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+
+    /// Conteúdo (texto ou qualquer JSON)
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl LogRecord {
+    /// Extrai o registro de uma notificação `notifications/message`
+    pub fn from_notification(notification: &McpNotification) -> Option<Self> {
+        if notification.method != LOG_MESSAGE_NOTIFICATION {
+            return None;
+        }
+        serde_json::from_value(notification.params.clone()?).ok()
+    }
+
+    /// Texto do registro, prefixado pelo logger quando houver
+    pub fn message(&self) -> String {
+        let data = match &self.data {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        match &self.logger {
+            Some(logger) => format!("[{}] {}", logger, data),
+            None => data,
+        }
+    }
+
+    /// Emite o registro no crate `log` com o target informado
+    pub fn emit(&self, target: &str) {
+        log::log!(target: target, self.level.to_log_level(), "{}", self.message());
+    }
+}
+
+/// Converte as notificações da conexão em stream de registros de log
+///
+/// Notificações perdidas por atraso do consumidor são descartadas com aviso;
+/// o stream termina quando a conexão é descartada.
+pub(crate) fn log_stream(
+    notifications: broadcast::Receiver<McpNotification>,
+) -> impl Stream<Item = LogRecord> + Send + 'static {
+    futures::stream::unfold(notifications, |mut notifications| async move {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => {
+                    if let Some(record) = LogRecord::from_notification(&notification) {
+                        return Some((record, notifications));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("{} notificações descartadas no stream de logs do servidor", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
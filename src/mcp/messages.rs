@@ -2,12 +2,88 @@
 //!
 //! Tipos de mensagem padronizados do protocolo MCP.
 
+use std::collections::HashMap;
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
+use crate::error::{SageXError, SageXResult};
 use crate::models::{McpError, UnixTimestamp};
 
+/// Versão do JSON-RPC usada no fio
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Códigos de erro padrão do JSON-RPC 2.0
+pub mod error_codes {
+    /// JSON inválido
+    pub const PARSE_ERROR: i32 = -32700;
+    /// Request inválido
+    pub const INVALID_REQUEST: i32 = -32600;
+    /// Método desconhecido
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    /// Parâmetros inválidos
+    pub const INVALID_PARAMS: i32 = -32602;
+    /// Erro interno
+    pub const INTERNAL_ERROR: i32 = -32603;
+}
+
+/// Token de progresso enviado em `_meta.progressToken` (string ou número)
+pub type ProgressToken = RequestId;
+
+/// Identificador de request JSON-RPC (string ou número)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequestId {
+    /// ID numérico
+    Number(i64),
+    /// ID textual
+    String(String),
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(id) => write!(f, "{}", id),
+            Self::String(id) => f.write_str(id),
+        }
+    }
+}
+
+impl From<String> for RequestId {
+    fn from(id: String) -> Self {
+        Self::String(id)
+    }
+}
+
+impl From<&str> for RequestId {
+    fn from(id: &str) -> Self {
+        Self::String(id.to_string())
+    }
+}
+
+impl From<i64> for RequestId {
+    fn from(id: i64) -> Self {
+        Self::Number(id)
+    }
+}
+
+impl PartialEq<str> for RequestId {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, Self::String(id) if id == other)
+    }
+}
+
+impl PartialEq<&str> for RequestId {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
 /// Envelope para todas as mensagens MCP
+///
+/// No fio, segue o JSON-RPC 2.0: o tipo da mensagem é inferido pela presença
+/// dos campos `method`, `id`, `result` e `error`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[serde(try_from = "WireMessage", into = "WireMessage")]
 pub enum McpMessage {
     /// Request - solicita uma ação do servidor
     Request(McpRequest),
@@ -19,11 +95,156 @@ pub enum McpMessage {
     Notification(McpNotification),
 }
 
+/// Payload JSON-RPC: uma mensagem ou um lote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum McpPayload {
+    /// Lote de mensagens
+    Batch(Vec<McpMessage>),
+    /// Mensagem única
+    Single(McpMessage),
+}
+
+impl McpPayload {
+    /// Desfaz o lote em mensagens individuais
+    pub fn into_messages(self) -> Vec<McpMessage> {
+        match self {
+            Self::Batch(messages) => messages,
+            Self::Single(message) => vec![message],
+        }
+    }
+}
+
+impl From<McpMessage> for McpPayload {
+    fn from(message: McpMessage) -> Self {
+        Self::Single(message)
+    }
+}
+
+impl From<Vec<McpMessage>> for McpPayload {
+    fn from(messages: Vec<McpMessage>) -> Self {
+        Self::Batch(messages)
+    }
+}
+
+/// Representação de uma mensagem JSON-RPC no fio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WireMessage {
+    /// Versão do protocolo (`"2.0"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    jsonrpc: Option<String>,
+    
+    /// ID do request ou da response; `Some(None)` representa `"id": null`
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    id: Option<Option<RequestId>>,
+    
+    /// Método do request ou da notificação
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
+    
+    /// Parâmetros do request ou da notificação
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    params: Option<serde_json::Value>,
+    
+    /// Resultado de sucesso; `Some(None)` representa `"result": null`
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    result: Option<Option<serde_json::Value>>,
+    
+    /// Erro
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<McpError>,
+}
+
+/// Marca o campo como presente, distinguindo `null` de ausente
+///
+/// Usado com `#[serde(default)]`: campos ausentes ficam `None` e campos
+/// nulos viram `Some(None)`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl TryFrom<WireMessage> for McpMessage {
+    type Error = String;
+    
+    fn try_from(wire: WireMessage) -> Result<Self, Self::Error> {
+        if let Some(version) = &wire.jsonrpc {
+            if version != JSONRPC_VERSION {
+                return Err(format!("versão JSON-RPC não suportada: {}", version));
+            }
+        }
+        
+        match (wire.method, wire.id) {
+            (Some(method), Some(Some(id))) => Ok(Self::Request(McpRequest {
+                id,
+                method,
+                params: wire.params,
+            })),
+            (Some(_), Some(None)) => Err("request JSON-RPC com `id` nulo".to_string()),
+            (Some(method), None) => Ok(Self::Notification(McpNotification {
+                method,
+                params: wire.params,
+                timestamp: None,
+            })),
+            // `id` nulo só é válido em erros de requests que o peer não conseguiu ler
+            (None, Some(id)) if wire.error.is_some() || (id.is_some() && wire.result.is_some()) => {
+                Ok(Self::Response(McpResponse {
+                    id,
+                    result: wire.result.map(|result| result.unwrap_or(serde_json::Value::Null)),
+                    error: wire.error,
+                }))
+            }
+            _ => Err("mensagem JSON-RPC sem `method` nem `id` com `result`/`error`".to_string()),
+        }
+    }
+}
+
+impl From<McpMessage> for WireMessage {
+    fn from(message: McpMessage) -> Self {
+        let wire = Self {
+            jsonrpc: Some(JSONRPC_VERSION.to_string()),
+            id: None,
+            method: None,
+            params: None,
+            result: None,
+            error: None,
+        };
+        
+        match message {
+            McpMessage::Request(req) => Self {
+                id: Some(Some(req.id)),
+                method: Some(req.method),
+                params: req.params,
+                ..wire
+            },
+            McpMessage::Response(resp) => Self {
+                id: Some(resp.id),
+                // JSON-RPC exige `result` em respostas de sucesso, mesmo que nulo
+                result: if resp.error.is_none() {
+                    Some(Some(resp.result.unwrap_or(serde_json::Value::Null)))
+                } else {
+                    None
+                },
+                error: resp.error,
+                ..wire
+            },
+            McpMessage::Notification(notif) => Self {
+                method: Some(notif.method),
+                params: notif.params,
+                ..wire
+            },
+        }
+    }
+}
+
 /// Request MCP
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpRequest {
     /// ID único do request
-    pub id: String,
+    pub id: RequestId,
     
     /// Método a ser executado
     pub method: String,
@@ -36,7 +257,10 @@ pub struct McpRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpResponse {
     /// ID do request correspondente
-    pub id: String,
+    ///
+    /// `None` em erros de requests que o peer não conseguiu identificar
+    /// (`"id": null` no fio).
+    pub id: Option<RequestId>,
     
     /// Resultado (se sucesso)
     pub result: Option<serde_json::Value>,
@@ -54,29 +278,30 @@ pub struct McpNotification {
     /// Parâmetros da notificação
     pub params: Option<serde_json::Value>,
     
-    /// Timestamp da notificação
+    /// Timestamp local da notificação (não trafega no fio)
+    #[serde(skip)]
     pub timestamp: Option<UnixTimestamp>,
 }
 
 impl McpMessage {
     /// Cria um novo request
-    pub fn new_request(id: String, method: String, params: Option<serde_json::Value>) -> Self {
-        Self::Request(McpRequest { id, method, params })
+    pub fn new_request(id: impl Into<RequestId>, method: String, params: Option<serde_json::Value>) -> Self {
+        Self::Request(McpRequest::new(id, method, params))
     }
     
     /// Cria uma nova response de sucesso
-    pub fn new_success_response(id: String, result: serde_json::Value) -> Self {
+    pub fn new_success_response(id: impl Into<RequestId>, result: serde_json::Value) -> Self {
         Self::Response(McpResponse {
-            id,
+            id: Some(id.into()),
             result: Some(result),
             error: None,
         })
     }
     
     /// Cria uma nova response de erro
-    pub fn new_error_response(id: String, error: McpError) -> Self {
+    pub fn new_error_response(id: impl Into<RequestId>, error: McpError) -> Self {
         Self::Response(McpResponse {
-            id,
+            id: Some(id.into()),
             result: None,
             error: Some(error),
         })
@@ -112,10 +337,10 @@ impl McpMessage {
     }
     
     /// Obtém o ID se for request ou response
-    pub fn id(&self) -> Option<&str> {
+    pub fn id(&self) -> Option<&RequestId> {
         match self {
             Self::Request(req) => Some(&req.id),
-            Self::Response(resp) => Some(&resp.id),
+            Self::Response(resp) => resp.id.as_ref(),
             Self::Notification(_) => None,
         }
     }
@@ -132,27 +357,58 @@ impl McpMessage {
 
 impl McpRequest {
     /// Cria um novo request
-    pub fn new(id: String, method: String, params: Option<serde_json::Value>) -> Self {
-        Self { id, method, params }
+    pub fn new(id: impl Into<RequestId>, method: String, params: Option<serde_json::Value>) -> Self {
+        Self { id: id.into(), method, params }
+    }
+    
+    /// Define o cursor de paginação nos parâmetros do request
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        if let Some(cursor) = cursor {
+            let params = self.params.get_or_insert_with(|| serde_json::json!({}));
+            if let Some(object) = params.as_object_mut() {
+                object.insert("cursor".to_string(), serde_json::Value::String(cursor));
+            }
+        }
+        self
+    }
+    
+    /// Anexa `_meta.progressToken` aos parâmetros do request
+    pub fn with_progress_token(mut self, token: ProgressToken) -> Self {
+        let params = self.params.get_or_insert_with(|| serde_json::json!({}));
+        if let Some(object) = params.as_object_mut() {
+            let meta = object.entry("_meta").or_insert_with(|| serde_json::json!({}));
+            if let Some(meta) = meta.as_object_mut() {
+                meta.insert("progressToken".to_string(), serde_json::json!(token));
+            }
+        }
+        self
+    }
+    
+    /// Obtém o `_meta.progressToken` do request, se houver
+    pub fn progress_token(&self) -> Option<ProgressToken> {
+        self.params.as_ref()
+            .and_then(|params| params.get("_meta"))
+            .and_then(|meta| meta.get("progressToken"))
+            .and_then(|token| serde_json::from_value(token.clone()).ok())
     }
     
     /// Cria um request ping
-    pub fn ping(id: String) -> Self {
+    pub fn ping(id: impl Into<RequestId>) -> Self {
         Self::new(id, "ping".to_string(), None)
     }
     
     /// Cria um request de inicialização
-    pub fn initialize(id: String, capabilities: serde_json::Value) -> Self {
+    pub fn initialize(id: impl Into<RequestId>, capabilities: serde_json::Value) -> Self {
         Self::new(id, "initialize".to_string(), Some(capabilities))
     }
     
     /// Cria um request para listar tools
-    pub fn list_tools(id: String) -> Self {
+    pub fn list_tools(id: impl Into<RequestId>) -> Self {
         Self::new(id, "tools/list".to_string(), None)
     }
     
     /// Cria um request para executar tool
-    pub fn call_tool(id: String, tool_name: String, arguments: serde_json::Value) -> Self {
+    pub fn call_tool(id: impl Into<RequestId>, tool_name: String, arguments: serde_json::Value) -> Self {
         Self::new(
             id,
             "tools/call".to_string(),
@@ -164,34 +420,88 @@ impl McpRequest {
     }
     
     /// Cria um request para listar resources
-    pub fn list_resources(id: String) -> Self {
+    pub fn list_resources(id: impl Into<RequestId>) -> Self {
         Self::new(id, "resources/list".to_string(), None)
     }
     
     /// Cria um request para obter resource
-    pub fn read_resource(id: String, uri: String) -> Self {
+    pub fn read_resource(id: impl Into<RequestId>, uri: String) -> Self {
         Self::new(
             id,
             "resources/read".to_string(),
             Some(serde_json::json!({ "uri": uri }))
         )
     }
+    
+    /// Cria um request para listar resource templates
+    pub fn list_resource_templates(id: impl Into<RequestId>) -> Self {
+        Self::new(id, "resources/templates/list".to_string(), None)
+    }
+    
+    /// Cria um request para assinar atualizações de um resource
+    pub fn subscribe_resource(id: impl Into<RequestId>, uri: String) -> Self {
+        Self::new(
+            id,
+            "resources/subscribe".to_string(),
+            Some(serde_json::json!({ "uri": uri }))
+        )
+    }
+    
+    /// Cria um request para cancelar a assinatura de um resource
+    pub fn unsubscribe_resource(id: impl Into<RequestId>, uri: String) -> Self {
+        Self::new(
+            id,
+            "resources/unsubscribe".to_string(),
+            Some(serde_json::json!({ "uri": uri }))
+        )
+    }
+    
+    /// Cria um request de completação de argumento
+    pub fn complete(id: impl Into<RequestId>, params: serde_json::Value) -> Self {
+        Self::new(id, "completion/complete".to_string(), Some(params))
+    }
+    
+    /// Cria um request para definir o nível mínimo de log do servidor
+    pub fn set_log_level(id: impl Into<RequestId>, level: LogLevel) -> Self {
+        Self::new(
+            id,
+            "logging/setLevel".to_string(),
+            Some(serde_json::json!({ "level": level }))
+        )
+    }
+    
+    /// Cria um request para listar prompts
+    pub fn list_prompts(id: impl Into<RequestId>) -> Self {
+        Self::new(id, "prompts/list".to_string(), None)
+    }
+    
+    /// Cria um request para renderizar um prompt
+    pub fn get_prompt(id: impl Into<RequestId>, name: String, arguments: HashMap<String, String>) -> Self {
+        Self::new(
+            id,
+            "prompts/get".to_string(),
+            Some(serde_json::json!({
+                "name": name,
+                "arguments": arguments
+            }))
+        )
+    }
 }
 
 impl McpResponse {
     /// Cria uma response de sucesso
-    pub fn success(id: String, result: serde_json::Value) -> Self {
+    pub fn success(id: impl Into<RequestId>, result: serde_json::Value) -> Self {
         Self {
-            id,
+            id: Some(id.into()),
             result: Some(result),
             error: None,
         }
     }
     
     /// Cria uma response de erro
-    pub fn error(id: String, code: i32, message: String, data: Option<serde_json::Value>) -> Self {
+    pub fn error(id: impl Into<RequestId>, code: i32, message: String, data: Option<serde_json::Value>) -> Self {
         Self {
-            id,
+            id: Some(id.into()),
             result: None,
             error: Some(McpError { code, message, data }),
         }
@@ -206,6 +516,14 @@ impl McpResponse {
     pub fn is_error(&self) -> bool {
         self.error.is_some()
     }
+    
+    /// Converte a response em resultado, mapeando erros JSON-RPC para `SageXError::McpRemote`
+    pub fn into_result(self) -> SageXResult<serde_json::Value> {
+        match self.error {
+            Some(error) => Err(SageXError::mcp_remote(error)),
+            None => Ok(self.result.unwrap_or(serde_json::Value::Null)),
+        }
+    }
 }
 
 impl McpNotification {
@@ -223,22 +541,35 @@ impl McpNotification {
         }
     }
     
+    /// Cria notificação de cancelamento de request
+    pub fn cancelled(request_id: RequestId, reason: Option<String>) -> Self {
+        let mut params = serde_json::json!({ "requestId": request_id });
+        if let Some(reason) = reason {
+            params["reason"] = serde_json::Value::String(reason);
+        }
+        Self::new("notifications/cancelled".to_string(), Some(params))
+    }
+    
     /// Cria notificação de progress
-    pub fn progress(progress_token: String, work_done: u64, total_work: Option<u64>) -> Self {
-        Self::new(
-            "notifications/progress".to_string(),
-            Some(serde_json::json!({
-                "progressToken": progress_token,
-                "value": {
-                    "kind": "report",
-                    "percentage": if let Some(total) = total_work {
-                        (work_done as f64 / total as f64 * 100.0) as u8
-                    } else {
-                        0
-                    }
-                }
-            }))
-        )
+    ///
+    /// `progress` deve crescer a cada notificação; `total` é opcional.
+    pub fn progress(
+        progress_token: impl Into<ProgressToken>,
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    ) -> Self {
+        let mut params = serde_json::json!({
+            "progressToken": progress_token.into(),
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = serde_json::json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = serde_json::Value::String(message);
+        }
+        Self::new("notifications/progress".to_string(), Some(params))
     }
     
     /// Cria notificação de log
@@ -263,18 +594,42 @@ impl McpNotification {
     }
 }
 
-/// Nível de log para notificações
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Nível de log para notificações (severidades da RFC 5424)
+///
+/// A ordenação segue a severidade: `Debug` é o menor e `Emergency` o maior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     /// Debug
     Debug,
     /// Info
     Info,
+    /// Evento normal, mas significativo
+    Notice,
     /// Warning
     Warning,
     /// Error
     Error,
+    /// Condição crítica
+    Critical,
+    /// Ação imediata necessária
+    Alert,
+    /// Sistema inutilizável
+    Emergency,
+}
+
+impl LogLevel {
+    /// Nível equivalente do crate `log`
+    ///
+    /// `Notice` vira `Info`; severidades acima de `Error` viram `Error`.
+    pub fn to_log_level(self) -> log::Level {
+        match self {
+            Self::Debug => log::Level::Debug,
+            Self::Info | Self::Notice => log::Level::Info,
+            Self::Warning => log::Level::Warn,
+            Self::Error | Self::Critical | Self::Alert | Self::Emergency => log::Level::Error,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -290,7 +645,7 @@ mod tests {
         );
         
         assert!(request.is_request());
-        assert_eq!(request.id(), Some("test-1"));
+        assert_eq!(request.id(), Some(&"test-1".into()));
         assert_eq!(request.method(), Some("ping"));
     }
     
@@ -302,6 +657,15 @@ mod tests {
         
         let list_tools = McpRequest::list_tools("tools-1".to_string());
         assert_eq!(list_tools.method, "tools/list");
+        assert!(list_tools.params.is_none());
+        
+        let next_page = McpRequest::list_tools("tools-2").with_cursor(Some("c2".to_string()));
+        assert_eq!(next_page.params, Some(serde_json::json!({"cursor": "c2"})));
+        
+        let tracked = McpRequest::call_tool("call-1", "index".to_string(), serde_json::json!({}))
+            .with_progress_token(RequestId::Number(9));
+        assert_eq!(tracked.params.as_ref().unwrap()["_meta"], serde_json::json!({"progressToken": 9}));
+        assert_eq!(tracked.progress_token(), Some(RequestId::Number(9)));
     }
     
     #[test]
@@ -341,6 +705,20 @@ mod tests {
         assert_eq!(log_notif.method, "notifications/message");
     }
     
+    #[test]
+    fn test_log_levels() {
+        let levels: Vec<LogLevel> = serde_json::from_value(serde_json::json!([
+            "debug", "info", "notice", "warning", "error", "critical", "alert", "emergency"
+        ])).unwrap();
+        assert!(levels.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(LogLevel::Notice.to_log_level(), log::Level::Info);
+        assert_eq!(LogLevel::Emergency.to_log_level(), log::Level::Error);
+        
+        let request = McpRequest::set_log_level("log-1", LogLevel::Critical);
+        assert_eq!(request.method, "logging/setLevel");
+        assert_eq!(request.params.unwrap()["level"], "critical");
+    }
+    
     #[test]
     fn test_serialization() {
         let request = McpMessage::new_request(
@@ -353,7 +731,170 @@ mod tests {
         let deserialized: McpMessage = serde_json::from_str(&serialized).unwrap();
         
         assert!(deserialized.is_request());
-        assert_eq!(deserialized.id(), Some("test-1"));
+        assert_eq!(deserialized.id(), Some(&"test-1".into()));
+    }
+    
+    #[test]
+    fn test_wire_format_is_jsonrpc() {
+        let request = McpMessage::new_request(1, "tools/list".to_string(), None);
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value, serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}));
+        
+        let notification = McpMessage::new_notification("notifications/initialized".to_string(), None);
+        let value = serde_json::to_value(&notification).unwrap();
+        assert_eq!(value, serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}));
+        
+        let response = McpMessage::new_success_response("a", serde_json::json!({}));
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value, serde_json::json!({"jsonrpc": "2.0", "id": "a", "result": {}}));
+    }
+    
+    #[test]
+    fn test_invalid_messages_are_rejected() {
+        let invalid = [
+            r#"{"jsonrpc": "1.0", "id": 1, "method": "ping"}"#,
+            r#"{"jsonrpc": "2.0", "id": 1}"#,
+            r#"{"jsonrpc": "2.0"}"#,
+            r#"{"jsonrpc": "2.0", "id": null, "method": "ping"}"#,
+            r#"{"jsonrpc": "2.0", "id": null, "result": {}}"#,
+        ];
+        
+        for json in invalid {
+            assert!(serde_json::from_str::<McpMessage>(json).is_err(), "{}", json);
+        }
+        
+        // Campos desconhecidos são ignorados, como em qualquer peer JSON-RPC
+        let tolerated: McpMessage = serde_json::from_str(r#"{"id": "x", "method": "ping", "extra": 1}"#).unwrap();
+        assert!(tolerated.is_request());
+    }
+    
+    /// Exemplos escritos à mão a partir da especificação JSON-RPC 2.0 e do MCP
+    ///
+    /// Não foram capturados de servidores reais; veja `tests/fixtures/jsonrpc/README.md`.
+    const FIXTURES: &[(&str, &str)] = &[
+        ("initialize_request", include_str!("../../tests/fixtures/jsonrpc/initialize_request.json")),
+        ("initialize_response", include_str!("../../tests/fixtures/jsonrpc/initialize_response.json")),
+        ("initialized_notification", include_str!("../../tests/fixtures/jsonrpc/initialized_notification.json")),
+        ("tools_list_response", include_str!("../../tests/fixtures/jsonrpc/tools_list_response.json")),
+        ("tools_call_request", include_str!("../../tests/fixtures/jsonrpc/tools_call_request.json")),
+        ("tools_call_response", include_str!("../../tests/fixtures/jsonrpc/tools_call_response.json")),
+        ("progress_notification", include_str!("../../tests/fixtures/jsonrpc/progress_notification.json")),
+        ("method_not_found_response", include_str!("../../tests/fixtures/jsonrpc/method_not_found_response.json")),
+        ("ping_request", include_str!("../../tests/fixtures/jsonrpc/ping_request.json")),
+        ("null_result_response", include_str!("../../tests/fixtures/jsonrpc/null_result_response.json")),
+        ("parse_error_response", include_str!("../../tests/fixtures/jsonrpc/parse_error_response.json")),
+    ];
+    
+    #[test]
+    fn test_spec_examples_round_trip() {
+        for (name, json) in FIXTURES {
+            let original: serde_json::Value = serde_json::from_str(json).unwrap();
+            let message: McpMessage = serde_json::from_value(original.clone())
+                .unwrap_or_else(|e| panic!("{}: {}", name, e));
+            let serialized = serde_json::to_value(&message).unwrap();
+            assert_eq!(serialized, original, "{}", name);
+        }
+    }
+    
+    /// Mensagens do servidor em transcrições capturadas (`captured/*/transcript.jsonl`)
+    fn captured_server_messages() -> Vec<(String, serde_json::Value)> {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/jsonrpc/captured");
+        let Ok(servers) = std::fs::read_dir(&root) else {
+            return Vec::new();
+        };
+        
+        let mut messages = Vec::new();
+        for server in servers {
+            let path = server.unwrap().path().join("transcript.jsonl");
+            let Ok(transcript) = std::fs::read_to_string(&path) else { continue };
+            assert!(path.with_file_name("SOURCE.md").exists(), "{} sem SOURCE.md", path.display());
+            for (line, entry) in transcript.lines().enumerate() {
+                let entry: serde_json::Value = serde_json::from_str(entry).unwrap();
+                if entry["direction"] == "server" {
+                    messages.push((format!("{}:{}", path.display(), line + 1), entry["message"].clone()));
+                }
+            }
+        }
+        messages
+    }
+    
+    #[test]
+    fn test_captured_transcripts_are_accepted() {
+        for (location, message) in captured_server_messages() {
+            serde_json::from_value::<McpPayload>(message).unwrap_or_else(|e| panic!("{}: {}", location, e));
+        }
+    }
+    
+    #[test]
+    fn test_spec_examples_kinds_and_ids() {
+        let parse = |json: &str| serde_json::from_str::<McpMessage>(json).unwrap();
+        
+        let request = parse(FIXTURES[0].1);
+        assert!(request.is_request());
+        assert_eq!(request.id(), Some(&RequestId::Number(0)));
+        
+        assert!(parse(FIXTURES[1].1).is_response());
+        assert!(parse(FIXTURES[2].1).is_notification());
+        
+        let call = parse(FIXTURES[4].1);
+        assert_eq!(call.id(), Some(&RequestId::String("call-42".to_string())));
+        
+        match parse(FIXTURES[7].1) {
+            McpMessage::Response(response) => {
+                assert!(response.is_error());
+                assert_eq!(response.error.unwrap().code, -32601);
+            }
+            other => panic!("esperava response, obteve {:?}", other),
+        }
+        
+        // `result: null` é um sucesso, não uma mensagem sem resultado
+        match parse(FIXTURES[9].1) {
+            McpMessage::Response(response) => {
+                assert!(response.is_success());
+                assert_eq!(response.result, Some(serde_json::Value::Null));
+            }
+            other => panic!("esperava response, obteve {:?}", other),
+        }
+        
+        // Erros de requests ilegíveis chegam com `id: null`
+        let parse_error = parse(FIXTURES[10].1);
+        assert!(parse_error.is_response());
+        assert_eq!(parse_error.id(), None);
+    }
+    
+    #[test]
+    fn test_batch_round_trip() {
+        let json = include_str!("../../tests/fixtures/jsonrpc/batch.json");
+        let original: serde_json::Value = serde_json::from_str(json).unwrap();
+        
+        let payload: McpPayload = serde_json::from_value(original.clone()).unwrap();
+        assert!(matches!(payload, McpPayload::Batch(_)));
+        assert_eq!(serde_json::to_value(&payload).unwrap(), original);
+        
+        let messages = payload.into_messages();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].is_request());
+        assert!(messages[2].is_notification());
+        
+        let single: McpPayload = serde_json::from_str(FIXTURES[8].1).unwrap();
+        assert_eq!(single.into_messages().len(), 1);
+    }
+    
+    #[test]
+    fn test_progress_notification_shape() {
+        let notification = McpNotification::progress(42, 50.0, Some(100.0), Some("Processando".to_string()));
+        let built = serde_json::to_value(McpMessage::Notification(notification)).unwrap();
+        let fixture: serde_json::Value = serde_json::from_str(FIXTURES[6].1).unwrap();
+        
+        assert_eq!(built["method"], fixture["method"]);
+        assert_eq!(built["params"]["progressToken"], fixture["params"]["progressToken"]);
+        assert_eq!(built["params"]["message"], fixture["params"]["message"]);
+        assert_eq!(built["params"]["progress"].as_f64(), fixture["params"]["progress"].as_f64());
+        assert_eq!(built["params"]["total"].as_f64(), fixture["params"]["total"].as_f64());
+        
+        let indeterminate = McpNotification::progress("t", 3.0, None, None);
+        let params = indeterminate.params.unwrap();
+        assert!(params.get("total").is_none());
+        assert!(params.get("message").is_none());
     }
 }
-
@@ -5,14 +5,57 @@
 pub mod protocol;
 pub mod messages;
 pub mod transport;
+pub mod types;
+pub mod handlers;
+pub mod sampling;
+pub mod roots;
+pub mod subscriptions;
+pub mod uri_template;
+pub mod completion;
+pub mod elicitation;
+pub mod logging;
+pub mod reconnect;
 
 // Re-exportações principais
-pub use protocol::{McpConnection, McpCapabilities};
-pub use messages::{McpMessage, McpRequest, McpResponse, McpNotification};
-pub use transport::{Transport, TransportType, HttpTransport, StdioTransport};
+pub use protocol::{
+    McpConnection, McpCapabilities, ProgressEvent, ProgressStream, RequestOptions, HeartbeatConfig, HeartbeatStats,
+    ConnectionEvent, ConnectionState,
+};
+pub use reconnect::{ReconnectPolicy, InFlightPolicy};
+pub use messages::{McpMessage, McpPayload, McpRequest, McpResponse, McpNotification, RequestId, ProgressToken, LogLevel};
+pub use transport::{
+    Transport, TransportType, HttpTransport, HttpMode, StdioTransport, ProcessTransport, ProcessConfig,
+    WebSocketTransport, WebSocketConfig,
+};
+pub use types::{
+    McpContent, ResourceContents, ToolCallResult, Paginated, ListToolsResult, ListResourcesResult,
+    Implementation, ClientCapabilities, ServerCapabilities, ListChangedCapability, ResourcesCapability,
+    InitializeParams, InitializeResult, ProgressUpdate, Role, McpPrompt, PromptArgument, PromptMessage,
+    ListPromptsResult, GetPromptResult, ReadResourceResult, ResourceTemplate, ListResourceTemplatesResult,
+    ResourceUpdated, CompletionReference, CompletionArgument, CompletionContext, CompleteParams, Completion,
+    CompleteResult,
+};
+pub use handlers::{HandlerRegistry, RequestContext, RequestHandler};
+pub use sampling::{
+    SamplingHandler, SamplingApproval, ApprovalDecision, SamplingRequestHandler, StubSamplingHandler,
+    SamplingMessage, ModelHint, ModelPreferences, IncludeContext, CreateMessageRequest, CreateMessageResult,
+};
+pub use elicitation::{
+    ElicitationHandler, ElicitationRequestHandler, ElicitationResponse, ScriptedElicitationHandler, ElicitRequest,
+    ElicitResult, ElicitAction, ElicitationSchema, PrimitiveSchema,
+};
+pub use logging::LogRecord;
+pub use roots::{Root, RootsProvider, ListRootsResult};
+pub use subscriptions::{ResourceSubscription, ResourceSubscriptions};
+pub use uri_template::UriTemplate;
+pub use completion::{Completer, CompletionOptions};
+pub use tokio_util::sync::CancellationToken;
 
-/// Versão do protocolo MCP suportada
-pub const MCP_VERSION: &str = "1.0.0";
+/// Versão do protocolo MCP solicitada no `initialize`
+pub const MCP_VERSION: &str = "2025-06-18";
+
+/// Versões do protocolo MCP aceitas na negociação (da mais recente para a mais antiga)
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
 
 /// Namespace para extensões SAGE-X
 pub const SAGE_X_NAMESPACE: &str = "sage-x";
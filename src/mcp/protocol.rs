@@ -3,40 +3,181 @@
 //! Implementa as funcionalidades principais do protocolo MCP.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
 use std::time::{Duration, SystemTime};
 
+use futures::future::BoxFuture;
+use futures::{FutureExt, Stream, StreamExt};
+use tokio::sync::Notify;
+use tokio::time::Instant;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::error::{SageXError, SageXResult};
-use super::messages::{McpMessage, McpRequest, McpResponse, McpNotification};
-use super::transport::{Transport, TransportType};
+use super::messages::{
+    error_codes, LogLevel, McpMessage, McpRequest, McpResponse, McpNotification, ProgressToken, RequestId,
+};
+use super::handlers::{HandlerRegistry, RequestContext};
+use super::elicitation::ELICITATION_METHOD;
+use super::logging::{log_stream, LogRecord};
+use super::reconnect::{InFlightPolicy, ReconnectPolicy};
+use super::roots::ROOTS_LIST_METHOD;
+use super::sampling::SAMPLING_METHOD;
+use super::transport::Transport;
+use super::types::{
+    ClientCapabilities, CompleteParams, CompleteResult, Completion, Implementation, InitializeParams, InitializeResult,
+    ListChangedCapability, ProgressUpdate, ServerCapabilities,
+};
+use super::{MCP_VERSION, SAGE_X_NAMESPACE, SUPPORTED_PROTOCOL_VERSIONS};
+
+/// Timeout padrão de requests quando nenhum é configurado
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Capacidade do canal de notificações (por assinante)
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 256;
+
+/// Capacidade do canal de eventos da conexão (por assinante)
+const EVENT_CHANNEL_CAPACITY: usize = 64;
 
 /// Representação de uma conexão MCP
-#[derive(Debug)]
+///
+/// Clones compartilham o mesmo transporte, estado e requests pendentes,
+/// funcionando como handles para a mesma conexão.
+#[derive(Debug, Clone)]
 pub struct McpConnection {
     /// ID único da conexão
     pub id: Uuid,
     
     /// Transporte usado pela conexão
-    transport: Box<dyn Transport>,
+    transport: Arc<RwLock<Box<dyn Transport>>>,
     
-    /// Capacidades negociadas
+    /// Capacidades SAGE-X locais
     capabilities: McpCapabilities,
     
+    /// Capacidades anunciadas ao servidor no `initialize`
+    client_capabilities: ClientCapabilities,
+    
+    /// Identificação enviada como `clientInfo`
+    client_info: Implementation,
+    
+    /// Resultado do `initialize` (versão, capacidades e dados do servidor)
+    server: Arc<RwLock<Option<InitializeResult>>>,
+    
     /// Estado da conexão
     state: Arc<RwLock<ConnectionState>>,
     
-    /// Canal para mensagens recebidas
-    message_sender: mpsc::UnboundedSender<McpMessage>,
+    /// Canal para notificações recebidas
+    notification_sender: broadcast::Sender<McpNotification>,
     
-    /// Canal para notificações
-    notification_sender: mpsc::UnboundedSender<McpNotification>,
+    /// Timeout padrão dos requests (sobreposto por `RequestOptions::timeout`)
+    default_timeout: Duration,
     
     /// Requests pendentes
-    pending_requests: Arc<RwLock<HashMap<String, PendingRequest>>>,
+    pending_requests: Arc<RwLock<HashMap<RequestId, PendingRequest>>>,
+    
+    /// Handlers para requests iniciados pelo peer
+    handlers: HandlerRegistry,
+    
+    /// Requests recebidos do peer em processamento, canceláveis via `notifications/cancelled`
+    inbound_requests: Arc<RwLock<HashMap<RequestId, CancellationToken>>>,
+    
+    /// Destinos das notificações de progresso, por token
+    ///
+    /// Mutex síncrono para permitir a remoção no `Drop` do stream.
+    progress_listeners: Arc<std::sync::Mutex<HashMap<ProgressToken, mpsc::UnboundedSender<ProgressUpdate>>>>,
+    
+    /// Task de leitura em background
+    reader_task: Arc<Mutex<Option<ReaderTask>>>,
+    
+    /// Configuração do heartbeat, se habilitado
+    heartbeat: Option<HeartbeatConfig>,
+    
+    /// Task de heartbeat em background
+    heartbeat_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    
+    /// Latência e pings perdidos medidos pelo heartbeat
+    heartbeat_stats: Arc<std::sync::Mutex<HeartbeatStats>>,
+    
+    /// Canal de eventos da conexão
+    event_sender: broadcast::Sender<ConnectionEvent>,
+    
+    /// Política de reconexão após perda do transporte
+    reconnect: ReconnectPolicy,
+    
+    /// Task de reconexão em andamento
+    reconnect_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+/// Task de leitura em background e seu sinal de encerramento
+#[derive(Debug)]
+struct ReaderTask {
+    /// Handle da task
+    handle: JoinHandle<()>,
+    
+    /// Sinaliza o encerramento da task
+    shutdown_sender: watch::Sender<bool>,
+}
+
+/// Referência fraca a uma conexão, mantida pelas tasks em background
+///
+/// Não impede que a conexão seja descartada: quando o último handle sai de
+/// escopo sem `disconnect()`, `upgrade` retorna `None` e a task termina.
+#[derive(Debug)]
+pub(crate) struct WeakConnection {
+    id: Uuid,
+    transport: Weak<RwLock<Box<dyn Transport>>>,
+    capabilities: McpCapabilities,
+    client_capabilities: ClientCapabilities,
+    client_info: Implementation,
+    server: Weak<RwLock<Option<InitializeResult>>>,
+    state: Weak<RwLock<ConnectionState>>,
+    notification_sender: broadcast::WeakSender<McpNotification>,
+    default_timeout: Duration,
+    pending_requests: Weak<RwLock<HashMap<RequestId, PendingRequest>>>,
+    handlers: HandlerRegistry,
+    inbound_requests: Weak<RwLock<HashMap<RequestId, CancellationToken>>>,
+    progress_listeners: Weak<std::sync::Mutex<HashMap<ProgressToken, mpsc::UnboundedSender<ProgressUpdate>>>>,
+    reader_task: Weak<Mutex<Option<ReaderTask>>>,
+    heartbeat: Option<HeartbeatConfig>,
+    heartbeat_task: Weak<Mutex<Option<JoinHandle<()>>>>,
+    heartbeat_stats: Weak<std::sync::Mutex<HeartbeatStats>>,
+    event_sender: broadcast::WeakSender<ConnectionEvent>,
+    reconnect: ReconnectPolicy,
+    reconnect_task: Weak<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl WeakConnection {
+    /// Obtém um handle da conexão, se ela ainda existir
+    pub(crate) fn upgrade(&self) -> Option<McpConnection> {
+        Some(McpConnection {
+            id: self.id,
+            transport: self.transport.upgrade()?,
+            capabilities: self.capabilities.clone(),
+            client_capabilities: self.client_capabilities.clone(),
+            client_info: self.client_info.clone(),
+            server: self.server.upgrade()?,
+            state: self.state.upgrade()?,
+            notification_sender: self.notification_sender.upgrade()?,
+            default_timeout: self.default_timeout,
+            pending_requests: self.pending_requests.upgrade()?,
+            handlers: self.handlers.clone(),
+            inbound_requests: self.inbound_requests.upgrade()?,
+            progress_listeners: self.progress_listeners.upgrade()?,
+            reader_task: self.reader_task.upgrade()?,
+            heartbeat: self.heartbeat.clone(),
+            heartbeat_task: self.heartbeat_task.upgrade()?,
+            heartbeat_stats: self.heartbeat_stats.upgrade()?,
+            event_sender: self.event_sender.upgrade()?,
+            reconnect: self.reconnect.clone(),
+            reconnect_task: self.reconnect_task.upgrade()?,
+        })
+    }
 }
 
 /// Estado da conexão MCP
@@ -46,6 +187,11 @@ pub enum ConnectionState {
     Connecting,
     /// Conectado e operacional
     Connected,
+    /// Restabelecendo o transporte após uma queda
+    Reconnecting {
+        /// Tentativa em andamento (a partir de 1)
+        attempt: u32,
+    },
     /// Desconectando
     Disconnecting,
     /// Desconectado
@@ -55,7 +201,11 @@ pub enum ConnectionState {
 }
 
 /// Capacidades do servidor/cliente MCP
+///
+/// Campos ausentes assumem o valor padrão, o que mantém compatíveis as
+/// configurações salvas no formato antigo (sem versão, logging e extensões).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct McpCapabilities {
     /// Versão do protocolo
     pub protocol_version: String,
@@ -85,7 +235,7 @@ pub struct McpCapabilities {
 impl Default for McpCapabilities {
     fn default() -> Self {
         Self {
-            protocol_version: super::MCP_VERSION.to_string(),
+            protocol_version: MCP_VERSION.to_string(),
             tools: true,
             resources: true,
             prompts: true,
@@ -103,11 +253,277 @@ struct PendingRequest {
     /// Timestamp do request
     timestamp: SystemTime,
     
+    /// Request original, reenviado após reconexão conforme a `InFlightPolicy`
+    request: McpRequest,
+    
     /// Sender para a resposta
-    response_sender: tokio::sync::oneshot::Sender<McpResponse>,
+    response_sender: tokio::sync::oneshot::Sender<SageXResult<McpResponse>>,
     
     /// Timeout do request
     timeout: Duration,
+    
+    /// Token de progresso do request, se houver
+    progress_token: Option<ProgressToken>,
+    
+    /// Sinalizado a cada notificação de progresso do request
+    progress_signal: Arc<Notify>,
+}
+
+/// Configuração do heartbeat da conexão
+///
+/// Pings periódicos detectam conexões meio abertas, que não reportam falha
+/// no transporte mas deixaram de responder.
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// Intervalo entre pings
+    pub interval: Duration,
+    
+    /// Tempo máximo de espera pelo pong
+    pub timeout: Duration,
+    
+    /// Pings seguidos sem resposta até considerar a conexão morta
+    pub max_missed: u32,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            max_missed: 3,
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Cria a configuração com o intervalo informado
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            ..Default::default()
+        }
+    }
+    
+    /// Define o tempo máximo de espera pelo pong
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// Define quantos pings seguidos podem ficar sem resposta
+    pub fn with_max_missed(mut self, max_missed: u32) -> Self {
+        self.max_missed = max_missed.max(1);
+        self
+    }
+}
+
+/// Medições do heartbeat
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeartbeatStats {
+    /// Latência do último ping respondido
+    pub last_rtt: Option<Duration>,
+    
+    /// Pings seguidos sem resposta
+    pub missed: u32,
+}
+
+/// Eventos da conexão, para monitoramento e reconexão
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// O estado da conexão mudou
+    StateChanged(ConnectionState),
+    
+    /// O peer respondeu ao ping do heartbeat
+    Pong {
+        /// Latência do ping
+        rtt: Duration,
+    },
+    
+    /// Um ping do heartbeat ficou sem resposta
+    PingMissed {
+        /// Pings seguidos sem resposta
+        missed: u32,
+    },
+    
+    /// A conexão foi considerada morta pelo heartbeat
+    HeartbeatFailed {
+        /// Pings seguidos sem resposta
+        missed: u32,
+    },
+    
+    /// O transporte e a sessão foram restabelecidos após uma queda
+    Reconnected {
+        /// Tentativas necessárias
+        attempts: u32,
+    },
+}
+
+/// Opções de envio de um request
+///
+/// Campos ausentes usam os padrões da conexão.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Timeout sem resposta (padrão: timeout da conexão)
+    pub timeout: Option<Duration>,
+    
+    /// Reinicia o timeout a cada notificação de progresso do request
+    pub reset_timeout_on_progress: bool,
+    
+    /// Limite absoluto de duração, mesmo com progresso
+    pub max_total_timeout: Option<Duration>,
+    
+    /// Token para abortar o request
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    /// Cria opções com os padrões da conexão
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Define o timeout sem resposta
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    
+    /// Reinicia o timeout a cada notificação de progresso
+    ///
+    /// Se o request não tiver `_meta.progressToken`, um token é anexado.
+    pub fn reset_timeout_on_progress(mut self) -> Self {
+        self.reset_timeout_on_progress = true;
+        self
+    }
+    
+    /// Define o limite absoluto de duração
+    pub fn with_max_total_timeout(mut self, max_total_timeout: Duration) -> Self {
+        self.max_total_timeout = Some(max_total_timeout);
+        self
+    }
+    
+    /// Define o token de cancelamento
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+}
+
+/// Evento produzido por `McpConnection::send_request_with_progress`
+#[derive(Debug)]
+pub enum ProgressEvent {
+    /// Notificação de progresso do servidor
+    Progress(ProgressUpdate),
+    
+    /// Resposta final (sempre o último evento)
+    Response(SageXResult<McpResponse>),
+}
+
+/// Stream de progresso de um request
+///
+/// Descartá-lo antes da resposta cancela o request no servidor.
+pub struct ProgressStream {
+    /// Token anexado ao request
+    progress_token: ProgressToken,
+    
+    /// Registro de destinos da conexão, para remoção no `Drop`
+    listeners: Arc<std::sync::Mutex<HashMap<ProgressToken, mpsc::UnboundedSender<ProgressUpdate>>>>,
+    
+    /// Notificações de progresso recebidas
+    updates: mpsc::UnboundedReceiver<ProgressUpdate>,
+    
+    /// Resposta pendente, `None` depois de entregue
+    response: Option<Pin<Box<dyn Future<Output = SageXResult<McpResponse>> + Send>>>,
+}
+
+impl ProgressStream {
+    /// Token de progresso anexado ao request
+    pub fn progress_token(&self) -> &ProgressToken {
+        &self.progress_token
+    }
+    
+    /// Aguarda a resposta final, descartando as notificações de progresso
+    pub async fn into_response(mut self) -> SageXResult<McpResponse> {
+        use futures::StreamExt;
+        
+        while let Some(event) = self.next().await {
+            if let ProgressEvent::Response(response) = event {
+                return response;
+            }
+        }
+        Err(SageXError::mcp_protocol("Stream de progresso encerrado sem resposta"))
+    }
+}
+
+impl Stream for ProgressStream {
+    type Item = ProgressEvent;
+    
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ProgressEvent>> {
+        let this = &mut *self;
+        let Some(response) = this.response.as_mut() else {
+            return Poll::Ready(None);
+        };
+        
+        // O progresso chega antes da resposta, então é drenado primeiro
+        if let Poll::Ready(Some(update)) = this.updates.poll_recv(cx) {
+            return Poll::Ready(Some(ProgressEvent::Progress(update)));
+        }
+        
+        match response.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                this.response = None;
+                this.listeners.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&this.progress_token);
+                Poll::Ready(Some(ProgressEvent::Response(result)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for ProgressStream {
+    fn drop(&mut self) {
+        self.listeners.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&self.progress_token);
+    }
+}
+
+impl std::fmt::Debug for ProgressStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressStream")
+            .field("progress_token", &self.progress_token)
+            .field("finished", &self.response.is_none())
+            .finish()
+    }
+}
+
+/// Cancela o request pendente se o future de `send_request` for descartado
+struct PendingGuard {
+    /// Conexão dona do request
+    connection: McpConnection,
+    
+    /// ID do request, `None` depois de desarmado
+    id: Option<RequestId>,
+}
+
+impl PendingGuard {
+    /// Indica que o request terminou normalmente
+    fn disarm(&mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else { return };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+        
+        let connection = self.connection.clone();
+        runtime.spawn(async move {
+            let reason = Some("Request descartado pelo cliente".to_string());
+            if let Err(e) = connection.cancel_request(&id, reason).await {
+                log::debug!("Falha ao cancelar request {} descartado: {}", id, e);
+            }
+        });
+    }
 }
 
 impl McpConnection {
@@ -119,197 +535,981 @@ impl McpConnection {
         let id = Uuid::new_v4();
         let state = Arc::new(RwLock::new(ConnectionState::Connecting));
         
-        let (message_sender, _message_receiver) = mpsc::unbounded_channel();
-        let (notification_sender, _notification_receiver) = mpsc::unbounded_channel();
+        let (notification_sender, _) = broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY);
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         
         let connection = Self {
             id,
-            transport,
+            transport: Arc::new(RwLock::new(transport)),
             capabilities,
+            client_capabilities: ClientCapabilities::default(),
+            client_info: Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            server: Arc::new(RwLock::new(None)),
             state,
-            message_sender,
             notification_sender,
+            default_timeout: DEFAULT_REQUEST_TIMEOUT,
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            handlers: HandlerRegistry::new(),
+            inbound_requests: Arc::new(RwLock::new(HashMap::new())),
+            progress_listeners: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            reader_task: Arc::new(Mutex::new(None)),
+            heartbeat: None,
+            heartbeat_task: Arc::new(Mutex::new(None)),
+            heartbeat_stats: Arc::new(std::sync::Mutex::new(HeartbeatStats::default())),
+            event_sender,
+            reconnect: ReconnectPolicy::disabled(),
+            reconnect_task: Arc::new(Mutex::new(None)),
         };
         
         Ok(connection)
     }
     
     /// Inicia a conexão
-    pub async fn connect(&mut self) -> SageXResult<()> {
-        {
-            let mut state = self.state.write().await;
-            *state = ConnectionState::Connecting;
+    pub async fn connect(&self) -> SageXResult<()> {
+        self.set_state(ConnectionState::Connecting).await;
+        
+        if let Err(e) = self.establish().await {
+            self.set_state(ConnectionState::Error(e.to_string())).await;
+            return Err(e);
         }
         
-        // Inicializar transporte
-        self.transport.initialize().await?;
+        self.set_state(ConnectionState::Connected).await;
+        self.start_heartbeat().await;
         
-        // Enviar handshake
-        let handshake_request = McpRequest {
-            id: Uuid::new_v4().to_string(),
-            method: "initialize".to_string(),
-            params: Some(serde_json::to_value(&self.capabilities)?),
-        };
+        Ok(())
+    }
+    
+    /// Inicializa o transporte, inicia a leitura e executa o handshake
+    async fn establish(&self) -> SageXResult<()> {
+        // A leitura em espera segura o transporte; sem pará-la, o lock de escrita não sai
+        self.stop_reader().await;
+        self.transport.write().await.initialize().await?;
         
-        let _response = self.send_request(handshake_request).await?;
+        // A task de leitura precisa estar ativa antes do handshake
+        self.start_reader().await;
         
-        {
-            let mut state = self.state.write().await;
-            *state = ConnectionState::Connected;
+        if let Err(e) = self.handshake().await {
+            self.stop_reader().await;
+            if let Err(close_error) = self.transport.write().await.close().await {
+                log::debug!("Falha ao fechar transporte após handshake inválido: {}", close_error);
+            }
+            return Err(e);
         }
         
         Ok(())
     }
     
-    /// Envia um request e aguarda resposta
-    pub async fn send_request(&self, request: McpRequest) -> SageXResult<McpResponse> {
-        let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
+    /// Habilita a reconexão automática após perda do transporte
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+    
+    /// Habilita o heartbeat, iniciado ao conectar
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatConfig) -> Self {
+        self.heartbeat = Some(heartbeat);
+        self
+    }
+    
+    /// Define as capacidades anunciadas ao servidor
+    pub fn with_client_capabilities(mut self, capabilities: ClientCapabilities) -> Self {
+        self.client_capabilities = capabilities;
+        self
+    }
+    
+    /// Usa um registro de handlers compartilhado (por exemplo, entre reconexões)
+    pub fn with_handlers(mut self, handlers: HandlerRegistry) -> Self {
+        self.handlers = handlers;
+        self
+    }
+    
+    /// Obtém o registro de handlers para requests iniciados pelo peer
+    pub fn handlers(&self) -> &HandlerRegistry {
+        &self.handlers
+    }
+    
+    /// Define o timeout padrão dos requests
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+    
+    /// Obtém o timeout padrão dos requests
+    pub fn request_timeout(&self) -> Duration {
+        self.default_timeout
+    }
+    
+    /// Define a identificação enviada como `clientInfo`
+    pub fn with_client_info(mut self, client_info: Implementation) -> Self {
+        self.client_info = client_info;
+        self
+    }
+    
+    /// Executa o `initialize` e envia `notifications/initialized`
+    ///
+    /// Aceita a versão escolhida pelo servidor se ela estiver em
+    /// `SUPPORTED_PROTOCOL_VERSIONS`; caso contrário a conexão é recusada.
+    async fn handshake(&self) -> SageXResult<()> {
+        // As capacidades SAGE-X seguem como extensão experimental
+        let mut capabilities = self.client_capabilities.clone();
+        capabilities
+            .experimental
+            .get_or_insert_with(HashMap::new)
+            .entry(SAGE_X_NAMESPACE.to_string())
+            .or_insert(serde_json::to_value(&self.capabilities)?);
         
-        let pending_request = PendingRequest {
-            timestamp: SystemTime::now(),
-            response_sender,
-            timeout: Duration::from_secs(30),
+        // Handlers registrados anunciam as capacidades correspondentes
+        if capabilities.sampling.is_none() && self.handlers.contains(SAMPLING_METHOD) {
+            capabilities.sampling = Some(serde_json::json!({}));
+        }
+        if capabilities.roots.is_none() && self.handlers.contains(ROOTS_LIST_METHOD) {
+            capabilities.roots = Some(ListChangedCapability { list_changed: true });
+        }
+        if capabilities.elicitation.is_none() && self.handlers.contains(ELICITATION_METHOD) {
+            capabilities.elicitation = Some(serde_json::json!({}));
+        }
+        
+        let params = InitializeParams {
+            protocol_version: MCP_VERSION.to_string(),
+            capabilities,
+            client_info: self.client_info.clone(),
         };
+        let request = McpRequest::initialize(Uuid::new_v4().to_string(), serde_json::to_value(&params)?);
         
-        // Armazenar request pendente
-        {
-            let mut pending = self.pending_requests.write().await;
-            pending.insert(request.id.clone(), pending_request);
+        let result = self.send_request(request).await?.into_result()?;
+        let result: InitializeResult = serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de initialize: {}", e)))?;
+        
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&result.protocol_version.as_str()) {
+            return Err(SageXError::mcp_protocol(format!(
+                "Versão do protocolo não suportada pelo cliente: {} (suportadas: {})",
+                result.protocol_version,
+                SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+            )));
         }
         
-        // Enviar request através do transporte
-        let message = McpMessage::Request(request.clone());
-        self.transport.send_message(message).await?;
+        if result.protocol_version != MCP_VERSION {
+            log::info!(
+                "Servidor {} negociou a versão {} do protocolo",
+                result.server_info.name, result.protocol_version
+            );
+        }
         
-        // Aguardar resposta ou timeout
-        let response = tokio::time::timeout(
-            Duration::from_secs(30),
-            response_receiver
-        ).await;
+        self.transport.read().await.set_protocol_version(&result.protocol_version).await;
+        *self.server.write().await = Some(result);
         
-        // Remover da lista de pendentes
-        {
-            let mut pending = self.pending_requests.write().await;
-            pending.remove(&request.id);
+        self.send_notification(McpNotification::new("notifications/initialized".to_string(), None)).await
+    }
+    
+    /// Inicia a task de leitura em background, se ainda não estiver ativa
+    async fn start_reader(&self) {
+        let mut reader_task = self.reader_task.lock().await;
+        if reader_task.as_ref().is_some_and(|task| !task.handle.is_finished()) {
+            return;
         }
         
-        match response {
-            Ok(Ok(response)) => Ok(response),
-            Ok(Err(_)) => Err(SageXError::mcp_protocol("Canal de resposta fechado")),
-            Err(_) => Err(SageXError::timeout(30, "Request MCP")),
-        }
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(Self::read_loop(self.downgrade(), shutdown_receiver));
+        
+        *reader_task = Some(ReaderTask { handle, shutdown_sender });
     }
     
-    /// Envia uma notificação
-    pub async fn send_notification(&self, notification: McpNotification) -> SageXResult<()> {
-        let message = McpMessage::Notification(notification);
-        self.transport.send_message(message).await?;
-        Ok(())
+    /// Sinaliza o encerramento da task de leitura e aguarda sua finalização
+    async fn stop_reader(&self) {
+        let reader_task = self.reader_task.lock().await.take();
+        if let Some(task) = reader_task {
+            let _ = task.shutdown_sender.send(true);
+            if let Err(e) = task.handle.await {
+                log::warn!("Task de leitura da conexão {} terminou com erro: {}", self.id, e);
+            }
+        }
     }
     
-    /// Envia uma resposta
-    pub async fn send_response(&self, response: McpResponse) -> SageXResult<()> {
-        let message = McpMessage::Response(response);
-        self.transport.send_message(message).await?;
-        Ok(())
+    /// Cria uma referência fraca à conexão
+    pub(crate) fn downgrade(&self) -> WeakConnection {
+        WeakConnection {
+            id: self.id,
+            transport: Arc::downgrade(&self.transport),
+            capabilities: self.capabilities.clone(),
+            client_capabilities: self.client_capabilities.clone(),
+            client_info: self.client_info.clone(),
+            server: Arc::downgrade(&self.server),
+            state: Arc::downgrade(&self.state),
+            notification_sender: self.notification_sender.downgrade(),
+            default_timeout: self.default_timeout,
+            pending_requests: Arc::downgrade(&self.pending_requests),
+            handlers: self.handlers.clone(),
+            inbound_requests: Arc::downgrade(&self.inbound_requests),
+            progress_listeners: Arc::downgrade(&self.progress_listeners),
+            reader_task: Arc::downgrade(&self.reader_task),
+            heartbeat: self.heartbeat.clone(),
+            heartbeat_task: Arc::downgrade(&self.heartbeat_task),
+            heartbeat_stats: Arc::downgrade(&self.heartbeat_stats),
+            event_sender: self.event_sender.downgrade(),
+            reconnect: self.reconnect.clone(),
+            reconnect_task: Arc::downgrade(&self.reconnect_task),
+        }
     }
     
-    /// Processa uma mensagem recebida
-    pub async fn handle_message(&self, message: McpMessage) -> SageXResult<()> {
-        match message {
-            McpMessage::Request(request) => {
-                // Encaminhar para handler de requests
-                self.handle_request(request).await?;
+    /// Loop de leitura: aguarda mensagens do transporte e as despacha
+    ///
+    /// Enquanto espera, mantém apenas o transporte; a conexão só é obtida ao
+    /// chegar uma mensagem, e o loop termina quando o último handle é descartado.
+    async fn read_loop(weak: WeakConnection, mut shutdown: watch::Receiver<bool>) {
+        loop {
+            if *shutdown.borrow() {
+                break;
             }
+            let Some(transport) = weak.transport.upgrade() else {
+                log::debug!("Conexão {} descartada sem disconnect; encerrando leitura", weak.id);
+                break;
+            };
             
-            McpMessage::Response(response) => {
-                // Localizar request pendente correspondente
-                let pending_request = {
-                    let mut pending = self.pending_requests.write().await;
-                    pending.remove(&response.id)
-                };
-                
-                if let Some(pending) = pending_request {
-                    let _ = pending.response_sender.send(response);
+            let received = {
+                let transport = transport.read().await;
+                tokio::select! {
+                    received = transport.next_message() => received,
+                    changed = shutdown.changed() => {
+                        // Sem sender, o último handle da conexão foi descartado
+                        if changed.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
                 }
-            }
+            };
+            drop(transport);
             
-            McpMessage::Notification(notification) => {
-                // Enviar através do canal de notificações
-                let _ = self.notification_sender.send(notification);
+            let Some(connection) = weak.upgrade() else {
+                log::debug!("Conexão {} descartada sem disconnect; encerrando leitura", weak.id);
+                break;
+            };
+            
+            match received {
+                Ok(Some(message)) => {
+                    if let Err(e) = connection.handle_message(message).await {
+                        log::warn!("Falha ao processar mensagem na conexão {}: {}", connection.id, e);
+                    }
+                }
+                
+                Ok(None) => {
+                    log::info!("Transporte da conexão {} foi desconectado", connection.id);
+                    connection.mark_lost(ConnectionState::Disconnected).await;
+                    break;
+                }
+                
+                Err(e) => {
+                    log::error!("Erro de leitura na conexão {}: {}", connection.id, e);
+                    connection.mark_lost(ConnectionState::Error(e.to_string())).await;
+                    break;
+                }
             }
         }
-        
-        Ok(())
     }
     
-    /// Manipula um request recebido
-    async fn handle_request(&self, request: McpRequest) -> SageXResult<()> {
-        // Implementação básica - em uma versão completa isso seria
-        // despachado para handlers específicos por método
+    /// Inicia o heartbeat, se configurado
+    async fn start_heartbeat(&self) {
+        let Some(config) = self.heartbeat.clone() else { return };
         
-        let response = match request.method.as_str() {
-            "ping" => McpResponse {
-                id: request.id,
-                result: Some(serde_json::json!({"pong": true})),
-                error: None,
-            },
-            
-            "capabilities" => McpResponse {
-                id: request.id,
-                result: Some(serde_json::to_value(&self.capabilities)?),
-                error: None,
-            },
-            
-            _ => McpResponse {
-                id: request.id,
-                result: None,
-                error: Some(crate::models::McpError {
-                    code: -32601,
-                    message: "Method not found".to_string(),
-                    data: None,
-                }),
-            }
-        };
+        let mut heartbeat_task = self.heartbeat_task.lock().await;
+        if let Some(task) = heartbeat_task.take() {
+            task.abort();
+        }
+        *self.stats() = HeartbeatStats::default();
         
-        self.send_response(response).await
+        *heartbeat_task = Some(tokio::spawn(Self::heartbeat_loop(self.downgrade(), config)));
     }
     
-    /// Obtém o estado atual da conexão
-    pub async fn state(&self) -> ConnectionState {
-        self.state.read().await.clone()
+    /// Interrompe o heartbeat
+    async fn stop_heartbeat(&self) {
+        if let Some(task) = self.heartbeat_task.lock().await.take() {
+            task.abort();
+        }
     }
     
-    /// Verifica se a conexão está ativa
-    pub async fn is_connected(&self) -> bool {
-        matches!(self.state().await, ConnectionState::Connected)
+    /// Envia pings periódicos e derruba a conexão após `max_missed` falhas seguidas
+    ///
+    /// Como a leitura, mantém apenas uma referência fraca entre os pings.
+    async fn heartbeat_loop(weak: WeakConnection, config: HeartbeatConfig) {
+        let mut ticker = tokio::time::interval_at(Instant::now() + config.interval, config.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        
+        loop {
+            ticker.tick().await;
+            let Some(connection) = weak.upgrade() else { break };
+            
+            let started = Instant::now();
+            let request = McpRequest::ping(Uuid::new_v4().to_string());
+            let options = RequestOptions::new().with_timeout(config.timeout);
+            let pong = connection.send_request_with_options(request, options).await;
+            
+            let missed = {
+                let mut stats = connection.stats();
+                match pong {
+                    Ok(_) => {
+                        stats.last_rtt = Some(started.elapsed());
+                        stats.missed = 0;
+                    }
+                    Err(_) => stats.missed += 1,
+                }
+                stats.missed
+            };
+            
+            if missed == 0 {
+                let rtt = started.elapsed();
+                log::trace!("Pong da conexão {} em {:?}", connection.id, rtt);
+                connection.emit(ConnectionEvent::Pong { rtt });
+                continue;
+            }
+            
+            log::warn!("Ping da conexão {} sem resposta ({}/{})", connection.id, missed, config.max_missed);
+            connection.emit(ConnectionEvent::PingMissed { missed });
+            
+            if missed >= config.max_missed {
+                // A própria task encerra o loop; o handle é apenas descartado
+                connection.heartbeat_task.lock().await.take();
+                
+                let reason = format!("Conexão sem resposta a {} pings seguidos", missed);
+                log::error!("{} ({})", reason, connection.id);
+                connection.emit(ConnectionEvent::HeartbeatFailed { missed });
+                
+                connection.stop_reader().await;
+                if let Err(e) = connection.transport.write().await.close().await {
+                    log::debug!("Falha ao fechar transporte da conexão {} sem resposta: {}", connection.id, e);
+                }
+                connection.mark_lost(ConnectionState::Error(reason)).await;
+                break;
+            }
+        }
     }
     
-    /// Obtém as capacidades negociadas
-    pub fn capabilities(&self) -> &McpCapabilities {
-        &self.capabilities
+    fn stats(&self) -> std::sync::MutexGuard<'_, HeartbeatStats> {
+        self.heartbeat_stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
     }
     
-    /// Obtém ID da conexão
-    pub fn id(&self) -> Uuid {
-        self.id
+    /// Publica um evento da conexão; sem assinantes, o evento é descartado
+    fn emit(&self, event: ConnectionEvent) {
+        let _ = self.event_sender.send(event);
     }
     
-    /// Fecha a conexão
-    pub async fn disconnect(&mut self) -> SageXResult<()> {
-        {
+    /// Registra a perda do transporte e libera os requests pendentes
+    ///
+    /// Com reconexão habilitada, uma conexão já estabelecida passa a
+    /// `Reconnecting` em vez do estado informado.
+    async fn mark_lost(&self, new_state: ConnectionState) {
+        let reconnectable = self.reconnect.is_enabled() && self.transport.read().await.can_reconnect();
+        let (new_state, reconnect) = {
             let mut state = self.state.write().await;
-            *state = ConnectionState::Disconnecting;
-        }
+            let reconnect = *state == ConnectionState::Connected && reconnectable;
+            let new_state = match *state {
+                ConnectionState::Connected if reconnect => {
+                    log::warn!("Conexão {} perdida ({:?}); reconectando", self.id, new_state);
+                    Some(ConnectionState::Reconnecting { attempt: 1 })
+                }
+                ConnectionState::Connecting | ConnectionState::Connected => Some(new_state),
+                _ => None,
+            };
+            if let Some(new_state) = &new_state {
+                *state = new_state.clone();
+            }
+            (new_state, reconnect)
+        };
+        if let Some(new_state) = new_state {
+            self.emit(ConnectionEvent::StateChanged(new_state));
+        }
+        self.stop_heartbeat().await;
         
-        self.transport.close().await?;
+        // Descartar os senders faz os requests em espera falharem imediatamente
+        let replay = reconnectable && self.reconnect.in_flight == InFlightPolicy::Replay;
+        self.pending_requests.write().await.retain(|_, pending| {
+            replay && !matches!(pending.request.method.as_str(), "initialize" | "ping")
+        });
+        self.cancel_inbound_requests().await;
         
-        {
+        if reconnect {
+            let connection = self.clone();
+            let policy = self.reconnect.clone();
+            *self.reconnect_task.lock().await = Some(tokio::spawn(async move {
+                connection.reconnect_loop(policy).await;
+            }));
+        }
+    }
+    
+    /// Tenta restabelecer a conexão com backoff até esgotar as tentativas
+    ///
+    /// Retorna um future boxed: reconectar reinicia a leitura, que pode
+    /// voltar a chamar `mark_lost`.
+    fn reconnect_loop(&self, policy: ReconnectPolicy) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            self.stop_reader().await;
+            if let Err(e) = self.transport.write().await.close().await {
+                log::debug!("Falha ao fechar transporte da conexão {} perdida: {}", self.id, e);
+            }
+        
+            for attempt in 1..=policy.backoff.max_retries {
+                self.set_state(ConnectionState::Reconnecting { attempt }).await;
+                let delay = policy.backoff.delay(attempt);
+                log::info!(
+                    "Reconectando {} em {:?} (tentativa {}/{})",
+                    self.id, delay, attempt, policy.backoff.max_retries
+                );
+                tokio::time::sleep(delay).await;
+            
+                match self.establish().await {
+                    Ok(()) => {
+                        self.replay_in_flight().await;
+                        self.set_state(ConnectionState::Connected).await;
+                        self.start_heartbeat().await;
+                        self.emit(ConnectionEvent::Reconnected { attempts: attempt });
+                        log::info!("Conexão {} restabelecida após {} tentativa(s)", self.id, attempt);
+                        self.reconnect_task.lock().await.take();
+                        return;
+                    }
+                    Err(e) => {
+                        log::warn!("Tentativa {} de reconexão de {} falhou: {}", attempt, self.id, e);
+                    }
+                }
+            }
+        
+            let reason = format!("Falha ao reconectar após {} tentativas", policy.backoff.max_retries);
+            log::error!("{} ({})", reason, self.id);
+            self.pending_requests.write().await.clear();
+            self.set_state(ConnectionState::Error(reason)).await;
+            self.reconnect_task.lock().await.take();
+        })
+    }
+    
+    /// Reenvia os requests mantidos durante a reconexão
+    async fn replay_in_flight(&self) {
+        let requests: Vec<McpRequest> = self.pending_requests.read().await
+            .values()
+            .filter(|pending| pending.request.method != "initialize")
+            .map(|pending| pending.request.clone())
+            .collect();
+        
+        let transport = self.transport.read().await;
+        for request in requests {
+            log::debug!("Reenviando request {} ({}) após reconexão", request.id, request.method);
+            if let Err(e) = transport.send_message(McpMessage::Request(request)).await {
+                log::warn!("Falha ao reenviar request na conexão {}: {}", self.id, e);
+            }
+        }
+    }
+    
+    /// Cancela todos os requests recebidos que ainda estão em processamento
+    async fn cancel_inbound_requests(&self) {
+        for (_, token) in self.inbound_requests.write().await.drain() {
+            token.cancel();
+        }
+    }
+    
+    /// Atualiza o estado da conexão
+    async fn set_state(&self, new_state: ConnectionState) {
+        let changed = {
             let mut state = self.state.write().await;
-            *state = ConnectionState::Disconnected;
+            let changed = *state != new_state;
+            *state = new_state.clone();
+            changed
+        };
+        if changed {
+            self.emit(ConnectionEvent::StateChanged(new_state));
+        }
+    }
+    
+    /// Envia um request e aguarda resposta
+    ///
+    /// Descartar o future antes da resposta cancela o request no servidor.
+    pub async fn send_request(&self, request: McpRequest) -> SageXResult<McpResponse> {
+        self.send_request_with_options(request, RequestOptions::default()).await
+    }
+    
+    /// Envia um request que pode ser abortado através do token
+    ///
+    /// Ao cancelar, o servidor recebe `notifications/cancelled` e o request
+    /// termina com `SageXError::Cancelled`.
+    pub async fn send_request_with_cancellation(
+        &self,
+        request: McpRequest,
+        cancel: CancellationToken,
+    ) -> SageXResult<McpResponse> {
+        self.send_request_with_options(request, RequestOptions::new().with_cancellation(cancel)).await
+    }
+    
+    /// Envia um request com timeout, reinício por progresso e cancelamento configuráveis
+    ///
+    /// Ao expirar, o servidor recebe `notifications/cancelled` e o request
+    /// termina com `SageXError::Timeout`.
+    pub async fn send_request_with_options(
+        &self,
+        mut request: McpRequest,
+        options: RequestOptions,
+    ) -> SageXResult<McpResponse> {
+        let timeout = options.timeout.unwrap_or(self.default_timeout);
+        let cancel = options.cancellation.unwrap_or_default();
+        
+        // Sem token o servidor não envia progresso e o timeout nunca seria reiniciado
+        if options.reset_timeout_on_progress && request.progress_token().is_none() {
+            request = request.with_progress_token(ProgressToken::String(Uuid::new_v4().to_string()));
+        }
+        
+        let (response_sender, mut response_receiver) = tokio::sync::oneshot::channel();
+        let progress_signal = Arc::new(Notify::new());
+        
+        let pending_request = PendingRequest {
+            timestamp: SystemTime::now(),
+            request: request.clone(),
+            response_sender,
+            timeout: options.max_total_timeout.unwrap_or(timeout),
+            progress_token: request.progress_token(),
+            progress_signal: progress_signal.clone(),
+        };
+        
+        // Armazenar request pendente
+        {
+            let mut pending = self.pending_requests.write().await;
+            pending.insert(request.id.clone(), pending_request);
+        }
+        
+        // A partir daqui, descartar o future cancela o request
+        let mut guard = PendingGuard {
+            connection: self.clone(),
+            id: Some(request.id.clone()),
+        };
+        
+        // Enviar request através do transporte
+        let message = McpMessage::Request(request.clone());
+        if let Err(e) = self.transport.read().await.send_message(message).await {
+            guard.disarm();
+            self.pending_requests.write().await.remove(&request.id);
+            return Err(e);
+        }
+        
+        let started = Instant::now();
+        let max_deadline = options.max_total_timeout.map(|max| started + max);
+        let mut deadline = started + timeout;
+        
+        // Aguardar resposta, cancelamento ou timeout
+        let response = loop {
+            let effective_deadline = max_deadline.map_or(deadline, |max| deadline.min(max));
+            
+            tokio::select! {
+                response = &mut response_receiver => break response,
+                _ = cancel.cancelled() => {
+                    guard.disarm();
+                    self.notify_abandoned(&request.id, "Cancelado pelo cliente").await;
+                    return Err(SageXError::cancelled(format!("Request MCP {}", request.method)));
+                }
+                _ = progress_signal.notified(), if options.reset_timeout_on_progress => {
+                    deadline = Instant::now() + timeout;
+                }
+                _ = tokio::time::sleep_until(effective_deadline) => {
+                    guard.disarm();
+                    self.notify_abandoned(&request.id, "Timeout").await;
+                    let elapsed = effective_deadline.duration_since(started);
+                    return Err(SageXError::timeout_after(elapsed, format!("Request MCP {}", request.method)));
+                }
+            }
+        };
+        guard.disarm();
+        
+        // Remover da lista de pendentes
+        {
+            let mut pending = self.pending_requests.write().await;
+            pending.remove(&request.id);
+        }
+        
+        match response {
+            Ok(response) => response,
+            Err(_) => Err(SageXError::mcp_protocol("Canal de resposta fechado")),
+        }
+    }
+    
+    /// Envia um request com `_meta.progressToken` e acompanha seu progresso
+    ///
+    /// O stream produz um `ProgressEvent::Progress` por notificação recebida e
+    /// termina com `ProgressEvent::Response`. O request é enviado no primeiro
+    /// poll; descartar o stream depois disso cancela o request.
+    pub fn send_request_with_progress(&self, request: McpRequest) -> ProgressStream {
+        self.send_request_with_progress_options(request, RequestOptions::default())
+    }
+    
+    /// Combina `send_request_with_progress` e `send_request_with_options`
+    pub fn send_request_with_progress_options(
+        &self,
+        request: McpRequest,
+        options: RequestOptions,
+    ) -> ProgressStream {
+        let progress_token = request.progress_token()
+            .unwrap_or_else(|| ProgressToken::String(Uuid::new_v4().to_string()));
+        let request = request.with_progress_token(progress_token.clone());
+        
+        // Registrar antes do envio para não perder as primeiras notificações
+        let (update_sender, updates) = mpsc::unbounded_channel();
+        self.lock_progress_listeners().insert(progress_token.clone(), update_sender);
+        
+        let connection = self.clone();
+        let response = Box::pin(async move {
+            connection.send_request_with_options(request, options).await
+        });
+        
+        ProgressStream {
+            progress_token,
+            listeners: self.progress_listeners.clone(),
+            updates,
+            response: Some(response),
+        }
+    }
+    
+    /// Acessa os destinos de progresso, tolerando lock envenenado
+    fn lock_progress_listeners(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<ProgressToken, mpsc::UnboundedSender<ProgressUpdate>>> {
+        self.progress_listeners.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    /// Encaminha uma notificação de progresso ao request e ao stream que aguardam o token
+    async fn route_progress(&self, notification: &McpNotification) {
+        let update = notification.params.clone()
+            .and_then(|params| serde_json::from_value::<ProgressUpdate>(params).ok());
+        let Some(update) = update else {
+            log::warn!("notifications/progress inválida na conexão {}", self.id);
+            return;
+        };
+        
+        // Reiniciar o timeout do request correspondente
+        {
+            let pending = self.pending_requests.read().await;
+            let request = pending.values()
+                .find(|request| request.progress_token.as_ref() == Some(&update.progress_token));
+            if let Some(request) = request {
+                request.progress_signal.notify_one();
+            }
         }
         
+        let mut listeners = self.lock_progress_listeners();
+        let Some(sender) = listeners.get(&update.progress_token) else {
+            log::debug!("Progresso para token desconhecido: {}", update.progress_token);
+            return;
+        };
+        
+        // Stream já descartado: liberar o registro
+        if let Err(mpsc::error::SendError(update)) = sender.send(update) {
+            listeners.remove(&update.progress_token);
+        }
+    }
+    
+    /// Cancela um request pendente, notificando o servidor com `notifications/cancelled`
+    ///
+    /// Retorna `false` se o request já tiver sido respondido (ou não existir).
+    /// Quem aguarda o request recebe `SageXError::Cancelled`.
+    pub async fn cancel_request(&self, id: &RequestId, reason: Option<String>) -> SageXResult<bool> {
+        let pending = self.pending_requests.write().await.remove(id);
+        let Some(pending) = pending else {
+            return Ok(false);
+        };
+        
+        let method = &pending.request.method;
+        let _ = pending.response_sender.send(Err(SageXError::cancelled(format!("Request MCP {}", method))));
+        
+        // O initialize não pode ser cancelado pelo protocolo
+        if method != "initialize" {
+            self.send_notification(McpNotification::cancelled(id.clone(), reason)).await?;
+        }
+        
+        Ok(true)
+    }
+    
+    /// Cancela um request abandonado pelo chamador
+    ///
+    /// Falhas ao notificar o servidor são apenas registradas: quem abandonou o
+    /// request deve receber o timeout ou o cancelamento, não o erro de envio.
+    async fn notify_abandoned(&self, id: &RequestId, reason: &str) {
+        if let Err(e) = self.cancel_request(id, Some(reason.to_string())).await {
+            log::warn!("Falha ao notificar cancelamento do request {} na conexão {}: {}", id, self.id, e);
+        }
+    }
+    
+    /// Pede sugestões ao servidor via `completion/complete`
+    ///
+    /// Falha se o servidor não anunciou a capacidade `completions`. Para
+    /// chamadas a cada tecla, prefira `Completer`, que agrupa e reaproveita respostas.
+    pub async fn complete(&self, params: &CompleteParams) -> SageXResult<Completion> {
+        let supported = self.capabilities().await.is_none_or(|c| c.completions.is_some());
+        if !supported {
+            return Err(SageXError::mcp_protocol("Servidor não suporta completion/complete"));
+        }
+        
+        let request = McpRequest::complete(Uuid::new_v4().to_string(), serde_json::to_value(params)?);
+        let result = self.send_request(request).await?.into_result()?;
+        let result: CompleteResult = serde_json::from_value(result)
+            .map_err(|e| SageXError::mcp_protocol(format!("Resultado inválido de completion/complete: {}", e)))?;
+        Ok(result.completion)
+    }
+    
+    /// Define o nível mínimo dos logs enviados pelo servidor via `logging/setLevel`
+    pub async fn set_log_level(&self, level: LogLevel) -> SageXResult<()> {
+        let supported = self.capabilities().await.is_none_or(|c| c.logging.is_some());
+        if !supported {
+            return Err(SageXError::mcp_protocol("Servidor não suporta logging/setLevel"));
+        }
+        
+        let request = McpRequest::set_log_level(Uuid::new_v4().to_string(), level);
+        self.send_request(request).await?.into_result()?;
+        Ok(())
+    }
+    
+    /// Stream dos registros de log (`notifications/message`) recebidos a partir de agora
+    pub fn log_records(&self) -> impl Stream<Item = LogRecord> + Send + 'static {
+        log_stream(self.subscribe_notifications())
+    }
+    
+    /// Repassa os logs do servidor ao crate `log`, com o nome do servidor como target
+    ///
+    /// A task termina quando a conexão é descartada; aborte o handle para parar antes.
+    pub async fn forward_logs(&self) -> JoinHandle<()> {
+        let target = self.initialize_result().await
+            .map(|result| result.server_info.name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "mcp-server".to_string());
+        let records = self.log_records();
+        
+        tokio::spawn(async move {
+            futures::pin_mut!(records);
+            while let Some(record) = records.next().await {
+                record.emit(&target);
+            }
+        })
+    }
+    
+    /// Envia uma notificação
+    pub async fn send_notification(&self, notification: McpNotification) -> SageXResult<()> {
+        let message = McpMessage::Notification(notification);
+        self.transport.read().await.send_message(message).await?;
+        Ok(())
+    }
+    
+    /// Envia uma resposta
+    pub async fn send_response(&self, response: McpResponse) -> SageXResult<()> {
+        let message = McpMessage::Response(response);
+        self.transport.read().await.send_message(message).await?;
+        Ok(())
+    }
+    
+    /// Processa uma mensagem recebida
+    pub async fn handle_message(&self, message: McpMessage) -> SageXResult<()> {
+        match message {
+            McpMessage::Request(request) => {
+                // Processar em paralelo para não bloquear a leitura (e permitir cancelamento)
+                let cancel = CancellationToken::new();
+                self.inbound_requests.write().await.insert(request.id.clone(), cancel.clone());
+                
+                let connection = self.clone();
+                tokio::spawn(async move {
+                    let id = request.id.clone();
+                    // Cancelamento tem prioridade: o handler pode terminar justamente por ter sido cancelado
+                    let outcome = tokio::select! {
+                        biased;
+                        _ = cancel.cancelled() => None,
+                        response = connection.handle_request(request, cancel.clone()) => Some(response),
+                    };
+                    connection.inbound_requests.write().await.remove(&id);
+                    
+                    let result = match outcome {
+                        Some(Ok(response)) => connection.send_response(response).await,
+                        Some(Err(e)) => Err(e),
+                        // Requests cancelados não recebem resposta
+                        None => {
+                            log::debug!("Request {} cancelado pelo peer", id);
+                            Ok(())
+                        }
+                    };
+                    if let Err(e) = result {
+                        log::warn!("Falha ao responder request {} na conexão {}: {}", id, connection.id, e);
+                    }
+                });
+            }
+            
+            McpMessage::Response(response) => {
+                let Some(id) = response.id.clone() else {
+                    // Sem id não há como saber qual request o peer rejeitou
+                    let message = response.error.map(|e| e.message).unwrap_or_default();
+                    log::warn!("Erro sem id recebido na conexão {}: {}", self.id, message);
+                    return Ok(());
+                };
+                
+                // Localizar request pendente correspondente
+                let pending_request = {
+                    let mut pending = self.pending_requests.write().await;
+                    pending.remove(&id)
+                };
+                
+                if let Some(pending) = pending_request {
+                    let _ = pending.response_sender.send(Ok(response));
+                } else {
+                    log::warn!("Response recebida para request desconhecido: {}", id);
+                }
+            }
+            
+            McpMessage::Notification(notification) => {
+                match notification.method.as_str() {
+                    "notifications/cancelled" => self.handle_cancelled(&notification).await,
+                    "notifications/progress" => self.route_progress(&notification).await,
+                    _ => {}
+                }
+                
+                // Enviar através do canal de notificações (sem assinantes é ok)
+                let _ = self.notification_sender.send(notification);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Cancela o processamento de um request recebido, se ainda estiver ativo
+    async fn handle_cancelled(&self, notification: &McpNotification) {
+        let request_id = notification.params.as_ref()
+            .and_then(|params| params.get("requestId"))
+            .and_then(|id| serde_json::from_value::<RequestId>(id.clone()).ok());
+        
+        let Some(request_id) = request_id else {
+            log::warn!("notifications/cancelled sem requestId válido na conexão {}", self.id);
+            return;
+        };
+        
+        if let Some(token) = self.inbound_requests.write().await.remove(&request_id) {
+            token.cancel();
+        }
+    }
+    
+    /// Manipula um request recebido e produz a resposta
+    ///
+    /// Handlers registrados têm precedência sobre os métodos embutidos.
+    async fn handle_request(&self, request: McpRequest, cancellation: CancellationToken) -> SageXResult<McpResponse> {
+        if let Some(handler) = self.handlers.get(&request.method) {
+            let context = RequestContext {
+                request_id: request.id.clone(),
+                method: request.method.clone(),
+                cancellation,
+                progress_token: request.progress_token(),
+                connection: self.clone(),
+            };
+            
+            // Um handler que entra em pânico ainda produz uma resposta de erro
+            let outcome = std::panic::AssertUnwindSafe(handler.handle(request.params, context))
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|_| Err(crate::models::McpError {
+                    code: error_codes::INTERNAL_ERROR,
+                    message: format!("Handler de {} falhou", request.method),
+                    data: None,
+                }));
+            
+            return Ok(match outcome {
+                Ok(result) => McpResponse::success(request.id, result),
+                Err(error) => McpResponse {
+                    id: Some(request.id),
+                    result: None,
+                    error: Some(error),
+                },
+            });
+        }
+        
+        let response = match request.method.as_str() {
+            "ping" => McpResponse {
+                id: Some(request.id),
+                result: Some(serde_json::json!({"pong": true})),
+                error: None,
+            },
+            
+            "capabilities" => McpResponse {
+                id: Some(request.id),
+                result: Some(serde_json::to_value(&self.capabilities)?),
+                error: None,
+            },
+            
+            _ => McpResponse {
+                id: Some(request.id),
+                result: None,
+                error: Some(crate::models::McpError {
+                    code: error_codes::METHOD_NOT_FOUND,
+                    message: "Method not found".to_string(),
+                    data: None,
+                }),
+            }
+        };
+        
+        Ok(response)
+    }
+    
+    /// Assina as notificações recebidas do peer
+    ///
+    /// Cada assinante recebe todas as notificações que chegarem após a assinatura.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<McpNotification> {
+        self.notification_sender.subscribe()
+    }
+    
+    /// Obtém o estado atual da conexão
+    pub async fn state(&self) -> ConnectionState {
+        self.state.read().await.clone()
+    }
+    
+    /// Assina os eventos da conexão (estado e heartbeat)
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.event_sender.subscribe()
+    }
+    
+    /// Obtém as medições do heartbeat
+    pub fn heartbeat_stats(&self) -> HeartbeatStats {
+        self.stats().clone()
+    }
+    
+    /// Verifica se a conexão está ativa
+    pub async fn is_connected(&self) -> bool {
+        matches!(self.state().await, ConnectionState::Connected)
+    }
+    
+    /// Obtém as capacidades negociadas com o servidor
+    ///
+    /// Retorna `None` antes de um `initialize` bem-sucedido.
+    pub async fn capabilities(&self) -> Option<ServerCapabilities> {
+        self.server.read().await.as_ref().map(|result| result.capabilities.clone())
+    }
+    
+    /// Obtém o resultado completo do `initialize`
+    pub async fn initialize_result(&self) -> Option<InitializeResult> {
+        self.server.read().await.clone()
+    }
+    
+    /// Obtém a versão do protocolo negociada
+    pub async fn protocol_version(&self) -> Option<String> {
+        self.server.read().await.as_ref().map(|result| result.protocol_version.clone())
+    }
+    
+    /// Obtém as capacidades SAGE-X locais
+    pub fn local_capabilities(&self) -> &McpCapabilities {
+        &self.capabilities
+    }
+    
+    /// Obtém ID da conexão
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+    
+    /// Fecha a conexão
+    pub async fn disconnect(&self) -> SageXResult<()> {
+        self.set_state(ConnectionState::Disconnecting).await;
+        
+        if let Some(task) = self.reconnect_task.lock().await.take() {
+            task.abort();
+        }
+        self.stop_heartbeat().await;
+        self.stop_reader().await;
+        self.cancel_inbound_requests().await;
+        self.transport.write().await.close().await?;
+        self.pending_requests.write().await.clear();
+        
+        self.set_state(ConnectionState::Disconnected).await;
+        
         Ok(())
     }
     
@@ -341,7 +1541,8 @@ impl McpConnection {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mcp::transport::MockTransport;
+    use crate::mcp::transport::{HttpTransport, MockTransport};
+    use crate::retry::Backoff;
     
     #[tokio::test]
     async fn test_connection_creation() {
@@ -358,15 +1559,1002 @@ mod tests {
     #[tokio::test]
     async fn test_capabilities() {
         let transport = Box::new(MockTransport::new());
-        let mut capabilities = McpCapabilities::default();
-        capabilities.tools = true;
-        capabilities.resources = false;
+        let capabilities = McpCapabilities {
+            tools: true,
+            resources: false,
+            ..Default::default()
+        };
         
         let connection = McpConnection::new(transport, capabilities.clone()).await.unwrap();
         
-        assert_eq!(connection.capabilities().tools, true);
-        assert_eq!(connection.capabilities().resources, false);
-        assert_eq!(connection.capabilities().protocol_version, super::super::MCP_VERSION);
+        assert!(connection.local_capabilities().tools);
+        assert!(!connection.local_capabilities().resources);
+        assert_eq!(connection.local_capabilities().protocol_version, MCP_VERSION);
+        assert!(connection.capabilities().await.is_none());
+    }
+    
+    /// Responde com sucesso a todo request enviado através do mock
+    fn spawn_responder(mock: MockTransport) {
+        spawn_responder_with_version(mock, MCP_VERSION);
+    }
+    
+    /// Responde ao `initialize` com o resultado da fixture na versão informada,
+    /// ignora métodos `slow/*` e responde `{}` aos demais requests
+    fn spawn_responder_with_version(mock: MockTransport, version: &'static str) {
+        spawn_responder_ignoring(mock, version, &[]);
+    }
+    
+    /// Como `spawn_responder_with_version`, sem responder aos métodos informados
+    fn spawn_responder_ignoring(mock: MockTransport, version: &'static str, ignored: &'static [&'static str]) {
+        let fixture: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/jsonrpc/initialize_response.json")).unwrap();
+        let mut initialize_result = fixture["result"].clone();
+        initialize_result["protocolVersion"] = serde_json::json!(version);
+        
+        tokio::spawn(async move {
+            let mut answered = 0;
+            loop {
+                let sent = mock.sent_messages().await;
+                for message in sent.iter().skip(answered) {
+                    if let McpMessage::Request(request) = message {
+                        if request.method.starts_with("slow/") || ignored.contains(&request.method.as_str()) {
+                            continue;
+                        }
+                        if request.method == "progress/run" {
+                            spawn_progress_reply(mock.clone(), request.clone(), 2, Duration::from_millis(30));
+                            continue;
+                        }
+                        if request.method == "progress/long" {
+                            spawn_progress_reply(mock.clone(), request.clone(), 5, Duration::from_millis(40));
+                            continue;
+                        }
+                        let result = if request.method == "initialize" {
+                            initialize_result.clone()
+                        } else {
+                            serde_json::json!({})
+                        };
+                        mock.add_incoming_message(McpMessage::new_success_response(
+                            request.id.clone(),
+                            result,
+                        )).await;
+                    }
+                }
+                answered = sent.len();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+    }
+    
+    #[tokio::test]
+    async fn test_connect_routes_response_through_reader() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        assert!(connection.is_connected().await);
+        
+        let response = connection.send_request(McpRequest::ping("ping-1".to_string())).await.unwrap();
+        assert_eq!(response.id, Some("ping-1".into()));
+        assert!(response.is_success());
+    }
+    
+    #[tokio::test]
+    async fn test_background_tasks_stop_when_connection_dropped() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_heartbeat(HeartbeatConfig::new(Duration::from_millis(5)));
+        connection.connect().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let state = Arc::downgrade(&connection.state);
+        drop(connection);
+        
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while state.upgrade().is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("tasks em background mantiveram a conexão viva");
+    }
+    
+    #[tokio::test]
+    async fn test_heartbeat_measures_rtt() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_heartbeat(HeartbeatConfig::new(Duration::from_millis(20)));
+        let mut events = connection.subscribe_events();
+        connection.connect().await.unwrap();
+        
+        assert_eq!(events.recv().await.unwrap(), ConnectionEvent::StateChanged(ConnectionState::Connected));
+        let pong = tokio::time::timeout(Duration::from_secs(2), events.recv()).await.unwrap().unwrap();
+        assert!(matches!(pong, ConnectionEvent::Pong { .. }));
+        
+        let stats = connection.heartbeat_stats();
+        assert!(stats.last_rtt.is_some());
+        assert_eq!(stats.missed, 0);
+        
+        connection.disconnect().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_heartbeat_marks_dead_connection() {
+        let mock = MockTransport::new();
+        spawn_responder_ignoring(mock.clone(), MCP_VERSION, &["ping"]);
+        
+        let heartbeat = HeartbeatConfig::new(Duration::from_millis(10))
+            .with_timeout(Duration::from_millis(20))
+            .with_max_missed(2);
+        let connection = McpConnection::new(Box::new(mock), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_heartbeat(heartbeat);
+        connection.connect().await.unwrap();
+        let mut events = connection.subscribe_events();
+        
+        let mut seen = Vec::new();
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(2), events.recv()).await.unwrap().unwrap();
+            let done = matches!(event, ConnectionEvent::StateChanged(_));
+            seen.push(event);
+            if done {
+                break;
+            }
+        }
+        
+        assert_eq!(seen[0], ConnectionEvent::PingMissed { missed: 1 });
+        assert_eq!(seen[1], ConnectionEvent::PingMissed { missed: 2 });
+        assert_eq!(seen[2], ConnectionEvent::HeartbeatFailed { missed: 2 });
+        assert!(matches!(seen[3], ConnectionEvent::StateChanged(ConnectionState::Error(_))));
+        assert!(matches!(connection.state().await, ConnectionState::Error(_)));
+        assert_eq!(connection.heartbeat_stats().missed, 2);
+    }
+    
+    /// Derruba o transporte compartilhado com a conexão
+    async fn drop_transport(mock: &MockTransport) {
+        let mut transport = mock.clone();
+        transport.close().await.unwrap();
+    }
+    
+    /// Conta os requests enviados com o método informado
+    async fn count_sent(mock: &MockTransport, method: &str) -> usize {
+        mock.sent_messages().await.iter().filter(|m| m.is_request() && m.method() == Some(method)).count()
+    }
+    
+    /// Aguarda o evento que satisfaz o predicado, descartando os demais
+    async fn wait_for_event(
+        events: &mut broadcast::Receiver<ConnectionEvent>,
+        predicate: impl Fn(&ConnectionEvent) -> bool,
+    ) -> ConnectionEvent {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                if predicate(&event) {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("evento não recebido")
+    }
+    
+    #[tokio::test]
+    async fn test_reconnect_redoes_handshake_and_fails_in_flight() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let policy = ReconnectPolicy::from(Backoff::default().with_delays(Duration::from_millis(10), Duration::from_millis(50)));
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_reconnect(policy);
+        connection.connect().await.unwrap();
+        let mut events = connection.subscribe_events();
+        
+        let in_flight = {
+            let connection = connection.clone();
+            tokio::spawn(async move { connection.send_request(McpRequest::new("slow-r1", "slow/index".to_string(), None)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop_transport(&mock).await;
+        
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::StateChanged(ConnectionState::Reconnecting { attempt: 1 })
+        );
+        assert!(in_flight.await.unwrap().is_err(), "InFlightPolicy::Fail libera o request");
+        
+        wait_for_event(&mut events, |e| *e == ConnectionEvent::Reconnected { attempts: 1 }).await;
+        assert!(connection.is_connected().await);
+        assert_eq!(count_sent(&mock, "initialize").await, 2);
+        assert_eq!(count_sent(&mock, "slow/index").await, 1);
+        
+        let response = connection.send_request(McpRequest::ping("ping-r1".to_string())).await.unwrap();
+        assert!(response.is_success());
+        
+        connection.disconnect().await.unwrap();
+        assert_eq!(connection.state().await, ConnectionState::Disconnected);
+    }
+    
+    #[tokio::test]
+    async fn test_stdio_connection_is_not_reconnected() {
+        use crate::mcp::transport::StdioTransport;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+        
+        let (peer, local) = tokio::io::duplex(64 * 1024);
+        let (reader, writer) = tokio::io::split(local);
+        let transport = StdioTransport::with_streams(Box::new(reader), Box::new(writer));
+        
+        // Peer que responde ao initialize e encerra o stream em seguida
+        let (peer_reader, mut peer_writer) = tokio::io::split(peer);
+        let peer = tokio::spawn(async move {
+            let fixture: serde_json::Value =
+                serde_json::from_str(include_str!("../../tests/fixtures/jsonrpc/initialize_response.json")).unwrap();
+            let mut lines = BufReader::new(peer_reader).lines();
+            let request: McpMessage = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+            let response = McpMessage::new_success_response(request.id().unwrap().clone(), fixture["result"].clone());
+            let line = format!("{}\n", serde_json::to_string(&response).unwrap());
+            peer_writer.write_all(line.as_bytes()).await.unwrap();
+            let _ = lines.next_line().await;
+        });
+        
+        let policy = ReconnectPolicy::from(Backoff::default().with_delays(Duration::from_millis(10), Duration::from_millis(50)));
+        let connection = McpConnection::new(Box::new(transport), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_reconnect(policy);
+        connection.connect().await.unwrap();
+        let mut events = connection.subscribe_events();
+        peer.await.unwrap();
+        
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ConnectionEvent::StateChanged(ConnectionState::Disconnected)
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_reconnect_replays_in_flight_requests() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let policy = ReconnectPolicy::from(Backoff::default().with_delays(Duration::from_millis(10), Duration::from_millis(50)))
+            .with_in_flight(InFlightPolicy::Replay);
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default())
+            .await
+            .unwrap()
+            .with_reconnect(policy);
+        connection.connect().await.unwrap();
+        let mut events = connection.subscribe_events();
+        
+        let in_flight = {
+            let connection = connection.clone();
+            tokio::spawn(async move { connection.send_request(McpRequest::new("slow-r2", "slow/index".to_string(), None)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop_transport(&mock).await;
+        
+        wait_for_event(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+        assert_eq!(count_sent(&mock, "slow/index").await, 2, "request reenviado após reconectar");
+        
+        mock.add_incoming_message(McpMessage::new_success_response("slow-r2", serde_json::json!({"ok": true}))).await;
+        let response = tokio::time::timeout(Duration::from_secs(2), in_flight).await.unwrap().unwrap().unwrap();
+        assert_eq!(response.into_result().unwrap()["ok"], true);
+    }
+    
+    #[tokio::test]
+    async fn test_initialize_handshake() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap()
+            .with_client_info(Implementation::new("sage-x-test", "0.0.1"));
+        connection.connect().await.unwrap();
+        
+        let sent = mock.sent_messages().await;
+        let McpMessage::Request(initialize) = &sent[0] else { panic!("initialize não enviado") };
+        let params: InitializeParams = serde_json::from_value(initialize.params.clone().unwrap()).unwrap();
+        assert_eq!(params.protocol_version, MCP_VERSION);
+        assert_eq!(params.client_info.name, "sage-x-test");
+        assert!(params.capabilities.experimental.unwrap().contains_key(SAGE_X_NAMESPACE));
+        assert!(matches!(&sent[1], McpMessage::Notification(n) if n.method == "notifications/initialized"));
+        
+        let capabilities = connection.capabilities().await.unwrap();
+        assert!(capabilities.tools.is_some_and(|t| t.list_changed));
+        let result = connection.initialize_result().await.unwrap();
+        assert_eq!(result.server_info.name, "example-server");
+        assert!(result.instructions.is_some());
+    }
+    
+    #[tokio::test]
+    async fn test_initialize_accepts_older_supported_version() {
+        let mock = MockTransport::new();
+        spawn_responder_with_version(mock.clone(), "2024-11-05");
+        
+        let connection = McpConnection::new(Box::new(mock), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        assert_eq!(connection.protocol_version().await.as_deref(), Some("2024-11-05"));
+    }
+    
+    #[tokio::test]
+    async fn test_streamable_http_sends_negotiated_protocol_version() {
+        let mut server = mockito::Server::new_async().await;
+        let initialize = server.mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"method": "initialize"})))
+            .match_header("mcp-protocol-version", mockito::Matcher::Missing)
+            .with_header("content-type", "application/json")
+            .with_header("mcp-session-id", "session-v")
+            .with_body_from_request(|request| {
+                let request: McpMessage = serde_json::from_slice(request.body().unwrap()).unwrap();
+                let result = serde_json::json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "serverInfo": {"name": "http-server", "version": "1.0.0"}
+                });
+                serde_json::to_vec(&McpMessage::new_success_response(request.id().unwrap().clone(), result)).unwrap()
+            })
+            .create_async()
+            .await;
+        let initialized = server.mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"method": "notifications/initialized"})))
+            .match_header("mcp-protocol-version", "2024-11-05")
+            .with_status(202)
+            .expect(1)
+            .create_async()
+            .await;
+        let listen = server.mock("GET", "/mcp")
+            .match_header("mcp-protocol-version", "2024-11-05")
+            .with_status(405)
+            .expect(1)
+            .create_async()
+            .await;
+        let delete = server.mock("DELETE", "/mcp")
+            .match_header("mcp-protocol-version", "2024-11-05")
+            .expect(1)
+            .create_async()
+            .await;
+        
+        let transport = HttpTransport::streamable(format!("{}/mcp", server.url()));
+        let connection = McpConnection::new(Box::new(transport), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !listen.matched_async().await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("GET de escuta não enviado com a versão negociada");
+        connection.disconnect().await.unwrap();
+        
+        initialize.assert_async().await;
+        initialized.assert_async().await;
+        listen.assert_async().await;
+        delete.assert_async().await;
+    }
+    
+    #[tokio::test]
+    async fn test_initialize_rejects_unsupported_version() {
+        let mock = MockTransport::new();
+        spawn_responder_with_version(mock.clone(), "1999-01-01");
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        let error = connection.connect().await.unwrap_err();
+        
+        assert!(matches!(error, SageXError::McpProtocol { .. }));
+        assert!(matches!(connection.state().await, ConnectionState::Error(_)));
+        assert!(!mock.is_connected().await);
+        assert!(!mock.sent_messages().await.iter().any(|m| m.method() == Some("notifications/initialized")));
+    }
+    
+    #[tokio::test]
+    async fn test_notifications_are_broadcast_to_subscribers() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let mut notifications = connection.subscribe_notifications();
+        mock.add_incoming_message(McpMessage::new_notification(
+            "notifications/tools/list_changed".to_string(),
+            None,
+        )).await;
+        
+        let notification = tokio::time::timeout(Duration::from_secs(1), notifications.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification.method, "notifications/tools/list_changed");
+    }
+    
+    #[tokio::test]
+    async fn test_inbound_request_is_answered() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        mock.add_incoming_message(McpMessage::Request(McpRequest::ping("server-ping".to_string()))).await;
+        
+        let answered = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                let sent = mock.sent_messages().await;
+                if sent.iter().any(|m| m.is_response() && m.id() == Some(&"server-ping".into())) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await;
+        assert!(answered.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_disconnect_stops_reader() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        connection.disconnect().await.unwrap();
+        assert_eq!(connection.state().await, ConnectionState::Disconnected);
+        assert!(connection.reader_task.lock().await.is_none());
+        assert!(!mock.is_connected().await);
+    }
+    
+    /// Envia `steps` notificações de progresso e depois a resposta, com pausas
+    /// para que o mock (LIFO) entregue na ordem
+    fn spawn_progress_reply(mock: MockTransport, request: McpRequest, steps: u32, interval: Duration) {
+        tokio::spawn(async move {
+            let token = request.progress_token().expect("request sem progressToken");
+            for step in 1..=steps {
+                mock.add_incoming_message(McpMessage::Notification(McpNotification::progress(
+                    token.clone(),
+                    step as f64,
+                    Some(steps as f64),
+                    Some(format!("passo {}", step)),
+                ))).await;
+                tokio::time::sleep(interval).await;
+            }
+            mock.add_incoming_message(McpMessage::new_success_response(
+                request.id.clone(),
+                serde_json::json!({"done": true}),
+            )).await;
+        });
+    }
+    
+    #[tokio::test]
+    async fn test_progress_stream_yields_updates_then_response() {
+        use futures::StreamExt;
+        
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let stream = connection.send_request_with_progress(McpRequest::new("p-1", "progress/run".to_string(), None));
+        let token = stream.progress_token().clone();
+        let events: Vec<ProgressEvent> = tokio::time::timeout(Duration::from_secs(2), stream.collect())
+            .await
+            .unwrap();
+        
+        assert_eq!(events.len(), 3);
+        let ProgressEvent::Progress(first) = &events[0] else { panic!("esperava progresso") };
+        assert_eq!(first.progress_token, token);
+        assert_eq!(first.message.as_deref(), Some("passo 1"));
+        let ProgressEvent::Progress(second) = &events[1] else { panic!("esperava progresso") };
+        assert_eq!(second.fraction(), Some(1.0));
+        let ProgressEvent::Response(Ok(response)) = &events[2] else { panic!("esperava resposta") };
+        assert_eq!(response.result, Some(serde_json::json!({"done": true})));
+        
+        assert!(connection.lock_progress_listeners().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_dropped_progress_stream_cancels_request() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let mut stream = connection.send_request_with_progress(McpRequest::new("slow-4", "slow/index".to_string(), None));
+        let poll = tokio::time::timeout(Duration::from_millis(20), futures::StreamExt::next(&mut stream)).await;
+        assert!(poll.is_err());
+        drop(stream);
+        
+        wait_for_cancelled(&mock, "slow-4").await;
+        assert!(connection.lock_progress_listeners().is_empty());
+    }
+    
+    /// Aguarda até que o mock envie `notifications/cancelled` para o request
+    async fn wait_for_cancelled(mock: &MockTransport, request_id: &str) -> McpNotification {
+        tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                for message in mock.sent_messages().await {
+                    if let McpMessage::Notification(n) = message {
+                        if n.method == "notifications/cancelled"
+                            && n.params.as_ref().is_some_and(|p| p["requestId"] == request_id)
+                        {
+                            return n;
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("notifications/cancelled não enviado")
+    }
+    
+    #[tokio::test]
+    async fn test_cancellation_token_aborts_request() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        
+        let request = McpRequest::new("slow-1", "slow/index".to_string(), None);
+        let error = connection.send_request_with_cancellation(request, cancel).await.unwrap_err();
+        assert!(matches!(error, SageXError::Cancelled { .. }));
+        
+        let notification = wait_for_cancelled(&mock, "slow-1").await;
+        assert_eq!(notification.params.unwrap()["reason"], "Cancelado pelo cliente");
+        assert!(connection.pending_requests.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_dropped_request_is_cancelled() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let request = McpRequest::new("slow-2", "slow/index".to_string(), None);
+        let result = tokio::time::timeout(Duration::from_millis(20), connection.send_request(request)).await;
+        assert!(result.is_err());
+        
+        wait_for_cancelled(&mock, "slow-2").await;
+        assert!(connection.pending_requests.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_request_by_id() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        let waiter = connection.clone();
+        let pending = tokio::spawn(async move {
+            waiter.send_request(McpRequest::new("slow-3", "slow/index".to_string(), None)).await
+        });
+        
+        let id = RequestId::from("slow-3");
+        let cancelled = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                if connection.cancel_request(&id, None).await.unwrap() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await;
+        assert!(cancelled.is_ok());
+        
+        assert!(matches!(pending.await.unwrap(), Err(SageXError::Cancelled { .. })));
+        assert!(!connection.cancel_request(&id, None).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_inbound_cancellation_cancels_handler() {
+        struct NotifyOnDrop(Arc<Notify>);
+        
+        impl Drop for NotifyOnDrop {
+            fn drop(&mut self) {
+                self.0.notify_one();
+            }
+        }
+        
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        // Handler lento que entrega o próprio token e avisa quando é descartado
+        let (started_sender, mut started) = tokio::sync::mpsc::unbounded_channel();
+        let stopped = Arc::new(Notify::new());
+        let stopped_signal = stopped.clone();
+        connection.handlers().register_fn("test/long", move |_: serde_json::Value, context: RequestContext| {
+            let started_sender = started_sender.clone();
+            let guard = NotifyOnDrop(stopped_signal.clone());
+            async move {
+                let _guard = guard;
+                let _ = started_sender.send(context.cancellation.clone());
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(serde_json::json!({"done": true}))
+            }
+        });
+        
+        let id = RequestId::Number(7);
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new(id.clone(), "test/long".to_string(), None))).await;
+        let token = tokio::time::timeout(Duration::from_secs(1), started.recv()).await.unwrap().unwrap();
+        assert!(!token.is_cancelled());
+        
+        mock.add_incoming_message(McpMessage::Notification(
+            McpNotification::cancelled(id.clone(), Some("usuário desistiu".to_string())),
+        )).await;
+        
+        // O handler observa o cancelamento e é interrompido sem responder
+        tokio::time::timeout(Duration::from_secs(1), token.cancelled()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), stopped.notified()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!mock.sent_messages().await.iter().any(|m| m.is_response() && m.id() == Some(&id)));
+        assert!(connection.inbound_requests.read().await.is_empty());
+    }
+    
+    async fn connected(mock: &MockTransport) -> McpConnection {
+        spawn_responder(mock.clone());
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        connection
+    }
+    
+    #[tokio::test]
+    async fn test_per_request_timeout() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        assert_eq!(connection.request_timeout(), DEFAULT_REQUEST_TIMEOUT);
+        
+        let options = RequestOptions::new().with_timeout(Duration::from_millis(50));
+        let error = connection
+            .send_request_with_options(McpRequest::new("slow-5", "slow/index".to_string(), None), options)
+            .await
+            .unwrap_err();
+        
+        assert!(matches!(error, SageXError::Timeout { millis: 50, .. }), "{:?}", error);
+        let notification = wait_for_cancelled(&mock, "slow-5").await;
+        assert_eq!(notification.params.unwrap()["reason"], "Timeout");
+        assert!(connection.pending_requests.read().await.is_empty());
+    }
+    
+    /// Transporte que não consegue enviar `notifications/cancelled`
+    #[derive(Debug)]
+    struct CancellationLost(MockTransport);
+    
+    #[async_trait::async_trait]
+    impl Transport for CancellationLost {
+        async fn initialize(&mut self) -> SageXResult<()> {
+            self.0.initialize().await
+        }
+        
+        async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+            if message.method() == Some("notifications/cancelled") {
+                return Err(SageXError::connection("Falha simulada no envio"));
+            }
+            self.0.send_message(message).await
+        }
+        
+        async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
+            self.0.receive_message().await
+        }
+        
+        async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+            self.0.next_message().await
+        }
+        
+        async fn close(&mut self) -> SageXResult<()> {
+            self.0.close().await
+        }
+        
+        async fn is_connected(&self) -> bool {
+            self.0.is_connected().await
+        }
+        
+        fn transport_type(&self) -> crate::mcp::transport::TransportType {
+            self.0.transport_type()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_abandoned_request_errors_survive_cancel_failure() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        let connection = McpConnection::new(Box::new(CancellationLost(mock.clone())), McpCapabilities::default()).await.unwrap();
+        connection.connect().await.unwrap();
+        
+        // A falha ao notificar o servidor não substitui o timeout
+        let options = RequestOptions::new().with_timeout(Duration::from_millis(30));
+        let error = connection
+            .send_request_with_options(McpRequest::new("slow-t", "slow/index".to_string(), None), options)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, SageXError::Timeout { .. }), "{:?}", error);
+        
+        // Nem o cancelamento
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let request = McpRequest::new("slow-c", "slow/index".to_string(), None);
+        let error = connection.send_request_with_cancellation(request, cancel).await.unwrap_err();
+        assert!(matches!(error, SageXError::Cancelled { .. }), "{:?}", error);
+        assert!(connection.pending_requests.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_connection_default_timeout() {
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap()
+            .with_request_timeout(Duration::from_millis(50));
+        connection.connect().await.unwrap();
+        
+        let error = connection.send_request(McpRequest::new("slow-6", "slow/index".to_string(), None)).await.unwrap_err();
+        assert!(matches!(error, SageXError::Timeout { .. }));
+    }
+    
+    #[tokio::test]
+    async fn test_timeout_resets_on_progress() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        // Sem reinício, 5 passos de 40ms estouram o timeout de 100ms
+        let options = RequestOptions::new().with_timeout(Duration::from_millis(100));
+        let request = McpRequest::new("long-1", "progress/long".to_string(), None)
+            .with_progress_token(RequestId::from("t-1"));
+        let error = connection.send_request_with_options(request, options.clone()).await.unwrap_err();
+        assert!(matches!(error, SageXError::Timeout { .. }));
+        
+        // Com reinício, cada progresso renova o prazo (o token é anexado automaticamente)
+        let request = McpRequest::new("long-2", "progress/long".to_string(), None);
+        let response = connection
+            .send_request_with_options(request, options.reset_timeout_on_progress())
+            .await
+            .unwrap();
+        assert!(response.is_success());
+    }
+    
+    #[tokio::test]
+    async fn test_max_total_timeout_caps_progress_resets() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        let options = RequestOptions::new()
+            .with_timeout(Duration::from_millis(100))
+            .reset_timeout_on_progress()
+            .with_max_total_timeout(Duration::from_millis(120));
+        let error = connection
+            .send_request_with_options(McpRequest::new("long-3", "progress/long".to_string(), None), options)
+            .await
+            .unwrap_err();
+        
+        assert!(matches!(error, SageXError::Timeout { .. }));
+        wait_for_cancelled(&mock, "long-3").await;
+    }
+    
+    /// Aguarda a resposta enviada pela conexão ao request do peer
+    async fn wait_for_response(mock: &MockTransport, request_id: &str) -> McpResponse {
+        let id = RequestId::from(request_id);
+        tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                for message in mock.sent_messages().await {
+                    if let McpMessage::Response(response) = message {
+                        if response.id.as_ref() == Some(&id) {
+                            return response;
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("resposta não enviada")
+    }
+    
+    #[derive(Debug, Deserialize)]
+    struct EchoParams {
+        text: String,
+    }
+    
+    #[tokio::test]
+    async fn test_registered_handler_with_typed_params() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        connection.handlers().register_fn("test/echo", |params: EchoParams, context: RequestContext| async move {
+            if params.text.is_empty() {
+                return Err(SageXError::validation("text", "vazio"));
+            }
+            Ok(serde_json::json!({ "echo": params.text, "method": context.method }))
+        });
+        
+        let call = |id: &str, params: serde_json::Value| {
+            McpMessage::Request(McpRequest::new(id, "test/echo".to_string(), Some(params)))
+        };
+        mock.add_incoming_message(call("e-1", serde_json::json!({"text": "oi"}))).await;
+        let response = wait_for_response(&mock, "e-1").await;
+        assert_eq!(response.result, Some(serde_json::json!({"echo": "oi", "method": "test/echo"})));
+        
+        mock.add_incoming_message(call("e-2", serde_json::json!({"texto": "oi"}))).await;
+        let response = wait_for_response(&mock, "e-2").await;
+        assert_eq!(response.error.unwrap().code, error_codes::INVALID_PARAMS);
+        
+        mock.add_incoming_message(call("e-3", serde_json::json!({"text": ""}))).await;
+        let error = wait_for_response(&mock, "e-3").await.error.unwrap();
+        assert_eq!(error.code, error_codes::INVALID_PARAMS);
+        assert!(error.message.contains("vazio"));
+    }
+    
+    #[tokio::test]
+    async fn test_slow_handler_does_not_block_reader() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        let release = Arc::new(Notify::new());
+        let gate = release.clone();
+        connection.handlers().register_fn("test/slow", move |_: serde_json::Value, _| {
+            let gate = gate.clone();
+            async move {
+                gate.notified().await;
+                Ok(serde_json::json!({"slow": true}))
+            }
+        });
+        
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new("s-1", "test/slow".to_string(), None))).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        mock.add_incoming_message(McpMessage::Request(McpRequest::ping("p-2"))).await;
+        
+        // O ping é respondido enquanto o handler lento ainda aguarda
+        wait_for_response(&mock, "p-2").await;
+        release.notify_one();
+        assert_eq!(wait_for_response(&mock, "s-1").await.result, Some(serde_json::json!({"slow": true})));
+    }
+    
+    #[tokio::test]
+    async fn test_handler_panic_and_cancellation() {
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        connection.handlers().register_fn("test/panic", |_: serde_json::Value, _| async move {
+            if true {
+                panic!("handler quebrado");
+            }
+            Ok(())
+        });
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new("x-1", "test/panic".to_string(), None))).await;
+        assert_eq!(wait_for_response(&mock, "x-1").await.error.unwrap().code, error_codes::INTERNAL_ERROR);
+        
+        let (observed_sender, observed) = tokio::sync::oneshot::channel();
+        let observed_sender = Arc::new(std::sync::Mutex::new(Some(observed_sender)));
+        connection.handlers().register_fn("test/wait", move |_: serde_json::Value, context: RequestContext| {
+            let observed_sender = observed_sender.clone();
+            async move {
+                let token = context.cancellation.clone();
+                if let Some(sender) = observed_sender.lock().unwrap().take() {
+                    let _ = sender.send(token.clone());
+                }
+                token.cancelled().await;
+                Ok(())
+            }
+        });
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new("w-1", "test/wait".to_string(), None))).await;
+        let token = tokio::time::timeout(Duration::from_secs(1), observed).await.unwrap().unwrap();
+        
+        mock.add_incoming_message(McpMessage::Notification(McpNotification::cancelled("w-1".into(), None))).await;
+        tokio::time::timeout(Duration::from_secs(1), token.cancelled()).await.unwrap();
+        
+        // Requests cancelados não recebem resposta
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!mock.sent_messages().await.iter().any(|m| m.is_response() && m.id() == Some(&"w-1".into())));
+    }
+    
+    #[tokio::test]
+    async fn test_sampling_handler_advertised_and_answered() {
+        use crate::mcp::sampling::{SamplingRequestHandler, StubSamplingHandler};
+        
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        let handlers = HandlerRegistry::new();
+        SamplingRequestHandler::new(StubSamplingHandler::new().with_response("Paris")).register(&handlers);
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap()
+            .with_handlers(handlers);
+        connection.connect().await.unwrap();
+        
+        let sent = mock.sent_messages().await;
+        let McpMessage::Request(initialize) = &sent[0] else { panic!("initialize não enviado") };
+        assert_eq!(initialize.params.as_ref().unwrap()["capabilities"]["sampling"], serde_json::json!({}));
+        
+        let params = serde_json::json!({
+            "messages": [{"role": "user", "content": {"type": "text", "text": "Capital da França?"}}],
+            "maxTokens": 10
+        });
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new(
+            "sm-1",
+            SAMPLING_METHOD.to_string(),
+            Some(params),
+        ))).await;
+        
+        let result = wait_for_response(&mock, "sm-1").await.result.unwrap();
+        assert_eq!(result["role"], "assistant");
+        assert_eq!(result["content"]["text"], "Paris");
+        assert_eq!(result["model"], StubSamplingHandler::MODEL);
+        
+        mock.add_incoming_message(McpMessage::Request(McpRequest::new("sm-2", SAMPLING_METHOD.to_string(), None))).await;
+        assert_eq!(wait_for_response(&mock, "sm-2").await.error.unwrap().code, error_codes::INVALID_PARAMS);
+    }
+    
+    #[tokio::test]
+    async fn test_set_log_level_and_log_stream() {
+        use futures::StreamExt;
+        
+        let mock = MockTransport::new();
+        let connection = connected(&mock).await;
+        
+        connection.set_log_level(LogLevel::Warning).await.unwrap();
+        let sent = mock.sent_messages().await;
+        let request = sent.iter().find(|m| m.method() == Some("logging/setLevel")).unwrap();
+        let McpMessage::Request(request) = request else { panic!("setLevel não é request") };
+        assert_eq!(request.params.as_ref().unwrap()["level"], "warning");
+        
+        let records = connection.log_records();
+        futures::pin_mut!(records);
+        mock.add_incoming_message(McpMessage::new_notification("notifications/tools/list_changed".to_string(), None)).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        mock.add_incoming_message(McpMessage::new_notification(
+            "notifications/message".to_string(),
+            Some(serde_json::json!({"level": "critical", "logger": "db", "data": {"error": "conexão perdida"}})),
+        )).await;
+        
+        let record = tokio::time::timeout(Duration::from_secs(2), records.next()).await.unwrap().unwrap();
+        assert_eq!(record.level, LogLevel::Critical);
+        assert_eq!(record.message(), r#"[db] {"error":"conexão perdida"}"#);
+    }
+    
+    #[tokio::test]
+    async fn test_elicitation_handler_advertised_and_answered() {
+        use crate::mcp::elicitation::{ElicitationRequestHandler, ScriptedElicitationHandler};
+        
+        let mock = MockTransport::new();
+        spawn_responder(mock.clone());
+        let handlers = HandlerRegistry::new();
+        let scripted = ScriptedElicitationHandler::new()
+            .accept(serde_json::json!({"confirm": true}))
+            .accept(serde_json::json!({"confirm": "sim"}));
+        ElicitationRequestHandler::new(scripted).register(&handlers);
+        
+        let connection = McpConnection::new(Box::new(mock.clone()), McpCapabilities::default()).await.unwrap()
+            .with_handlers(handlers);
+        connection.connect().await.unwrap();
+        
+        let sent = mock.sent_messages().await;
+        let McpMessage::Request(initialize) = &sent[0] else { panic!("initialize não enviado") };
+        assert_eq!(initialize.params.as_ref().unwrap()["capabilities"]["elicitation"], serde_json::json!({}));
+        
+        let params = serde_json::json!({
+            "message": "Confirmar deploy?",
+            "requestedSchema": {"type": "object", "properties": {"confirm": {"type": "boolean"}}, "required": ["confirm"]}
+        });
+        let elicit = |id: &str| McpMessage::Request(McpRequest::new(id, ELICITATION_METHOD.to_string(), Some(params.clone())));
+        
+        mock.add_incoming_message(elicit("el-1")).await;
+        let result = wait_for_response(&mock, "el-1").await.result.unwrap();
+        assert_eq!(result, serde_json::json!({"action": "accept", "content": {"confirm": true}}));
+        
+        // Conteúdo fora do schema não é repassado ao servidor
+        mock.add_incoming_message(elicit("el-2")).await;
+        assert_eq!(wait_for_response(&mock, "el-2").await.error.unwrap().code, error_codes::INVALID_PARAMS);
     }
 }
-
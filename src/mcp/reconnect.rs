@@ -0,0 +1,58 @@
+//! Política de reconexão automática
+//!
+//! Quando o transporte cai, a conexão tenta restabelecê-lo com backoff
+//! exponencial e jitter, refaz o `initialize` e decide, pela
+//! `InFlightPolicy`, se os requests em andamento falham ou são reenviados.
+
+use crate::retry::Backoff;
+
+/// Tratamento dos requests em andamento quando a conexão cai
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InFlightPolicy {
+    /// Falham imediatamente, como sem reconexão
+    #[default]
+    Fail,
+
+    /// Aguardam a reconexão e são reenviados, respeitando o próprio timeout
+    ///
+    /// Adequado apenas a requests idempotentes: o servidor pode já ter
+    /// processado o request antes da queda.
+    Replay,
+}
+
+/// Política de reconexão de uma `McpConnection`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Tentativas e intervalos; zero tentativas desabilita a reconexão
+    pub backoff: Backoff,
+
+    /// Tratamento dos requests em andamento
+    pub in_flight: InFlightPolicy,
+}
+
+impl From<Backoff> for ReconnectPolicy {
+    fn from(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            in_flight: InFlightPolicy::Fail,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Política que nunca reconecta
+    pub fn disabled() -> Self {
+        Backoff::disabled().into()
+    }
+
+    /// Define o tratamento dos requests em andamento
+    pub fn with_in_flight(mut self, in_flight: InFlightPolicy) -> Self {
+        self.in_flight = in_flight;
+        self
+    }
+
+    /// Verifica se a reconexão está habilitada
+    pub fn is_enabled(&self) -> bool {
+        self.backoff.is_enabled()
+    }
+}
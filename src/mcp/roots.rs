@@ -0,0 +1,198 @@
+//! Roots: diretórios do workspace expostos ao servidor
+//!
+//! O servidor consulta `roots/list` para saber onde pode operar. As raízes vêm
+//! da sessão de desenvolvimento ativa e de diretórios extras configurados; a
+//! cada mudança o cliente envia `notifications/roots/list_changed`.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SageXError, SageXResult};
+use crate::models::SessionContext;
+use super::handlers::HandlerRegistry;
+
+/// Método JSON-RPC de listagem de roots
+pub const ROOTS_LIST_METHOD: &str = "roots/list";
+
+/// Notificação enviada quando as roots mudam
+pub const ROOTS_LIST_CHANGED: &str = "notifications/roots/list_changed";
+
+/// Raiz do workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Root {
+    /// URI `file://` do diretório
+    pub uri: String,
+
+    /// Nome legível
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Root {
+    /// Cria uma raiz a partir de um diretório local
+    ///
+    /// Caminhos relativos são resolvidos contra o diretório atual.
+    pub fn from_path<P: AsRef<Path>>(path: P, name: Option<String>) -> SageXResult<Self> {
+        let path = std::path::absolute(path.as_ref())?;
+        let uri = reqwest::Url::from_file_path(&path)
+            .map_err(|_| SageXError::validation("path", format!("Caminho inválido para root: {}", path.display())))?;
+
+        Ok(Self {
+            uri: uri.to_string(),
+            name,
+        })
+    }
+}
+
+/// Resultado de `roots/list`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListRootsResult {
+    /// Raízes disponíveis
+    pub roots: Vec<Root>,
+}
+
+/// Estado das roots anunciadas
+#[derive(Debug, Default)]
+struct RootsState {
+    /// Raiz da sessão ativa
+    session: Option<Root>,
+
+    /// Diretórios extras configurados
+    extra: Vec<Root>,
+}
+
+/// Fonte das roots respondidas em `roots/list`
+///
+/// Clones compartilham o mesmo estado; o cliente atualiza a raiz da sessão ao
+/// iniciar e finalizar sessões.
+#[derive(Debug, Clone, Default)]
+pub struct RootsProvider {
+    state: Arc<RwLock<RootsState>>,
+}
+
+impl RootsProvider {
+    /// Cria um provedor sem roots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cria um provedor com diretórios extras
+    pub fn with_directories<P: AsRef<Path>>(directories: &[P]) -> SageXResult<Self> {
+        let provider = Self::new();
+        for directory in directories {
+            provider.add_directory(directory)?;
+        }
+        Ok(provider)
+    }
+
+    /// Adiciona um diretório extra, nomeado pelo último componente do caminho
+    pub fn add_directory<P: AsRef<Path>>(&self, directory: P) -> SageXResult<Root> {
+        let directory = directory.as_ref();
+        let name = directory.file_name().map(|name| name.to_string_lossy().into_owned());
+        let root = Root::from_path(directory, name)?;
+
+        let mut state = self.write();
+        if !state.extra.iter().any(|r| r.uri == root.uri) {
+            state.extra.push(root.clone());
+        }
+        Ok(root)
+    }
+
+    /// Remove um diretório extra; retorna se havia algo a remover
+    pub fn remove_directory<P: AsRef<Path>>(&self, directory: P) -> SageXResult<bool> {
+        let root = Root::from_path(directory, None)?;
+        let mut state = self.write();
+        let before = state.extra.len();
+        state.extra.retain(|r| r.uri != root.uri);
+        Ok(state.extra.len() != before)
+    }
+
+    /// Define a raiz a partir do contexto da sessão ativa
+    pub fn set_session(&self, context: &SessionContext) -> SageXResult<()> {
+        let root = Root::from_path(PathBuf::from(&context.working_directory), context.project_name.clone())?;
+        self.write().session = Some(root);
+        Ok(())
+    }
+
+    /// Remove a raiz da sessão; retorna se havia uma
+    pub fn clear_session(&self) -> bool {
+        self.write().session.take().is_some()
+    }
+
+    /// Lista as roots atuais, a da sessão primeiro e sem duplicatas
+    pub fn roots(&self) -> Vec<Root> {
+        let state = self.read();
+        let mut roots: Vec<Root> = state.session.iter().cloned().collect();
+        for root in &state.extra {
+            if !roots.iter().any(|r| r.uri == root.uri) {
+                roots.push(root.clone());
+            }
+        }
+        roots
+    }
+
+    /// Registra o provedor em `roots/list`
+    ///
+    /// A capacidade `roots` (com `listChanged`) passa a ser anunciada no `initialize`.
+    pub fn register(&self, registry: &HandlerRegistry) {
+        let provider = self.clone();
+        registry.register_fn(ROOTS_LIST_METHOD, move |_: serde_json::Value, _| {
+            let roots = provider.roots();
+            async move { Ok(ListRootsResult { roots }) }
+        });
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, RootsState> {
+        self.state.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, RootsState> {
+        self.state.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn context(directory: &str) -> SessionContext {
+        SessionContext {
+            working_directory: directory.to_string(),
+            project_name: Some("sage-x".to_string()),
+            git_branch: None,
+            technologies: Vec::new(),
+            environment: HashMap::new(),
+            editor_config: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_root_uri_encoding() {
+        let root = Root::from_path("/home/dev/meu projeto", None).unwrap();
+        assert_eq!(root.uri, "file:///home/dev/meu%20projeto");
+
+        let value = serde_json::to_value(&root).unwrap();
+        assert!(value.get("name").is_none());
+    }
+
+    #[test]
+    fn test_session_and_extra_roots() {
+        let provider = RootsProvider::with_directories(&["/srv/shared", "/srv/docs"]).unwrap();
+        provider.set_session(&context("/home/dev/sage-x")).unwrap();
+        provider.add_directory("/srv/docs").unwrap();
+
+        let roots = provider.roots();
+        let uris: Vec<&str> = roots.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(uris, ["file:///home/dev/sage-x", "file:///srv/shared", "file:///srv/docs"]);
+        assert_eq!(roots[0].name.as_deref(), Some("sage-x"));
+        assert_eq!(roots[1].name.as_deref(), Some("shared"));
+
+        assert!(provider.clear_session());
+        assert!(!provider.clear_session());
+        assert!(provider.remove_directory("/srv/docs").unwrap());
+        assert_eq!(provider.roots().len(), 1);
+    }
+}
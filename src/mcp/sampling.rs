@@ -0,0 +1,367 @@
+//! Sampling: servidores pedindo ao cliente uma completação de LLM
+//!
+//! O servidor envia `sampling/createMessage`; a aplicação decide qual modelo
+//! usar implementando `SamplingHandler`. Um `SamplingApproval` opcional permite
+//! que um humano revise (ou rejeite) o pedido antes do envio ao modelo.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SageXError, SageXResult};
+use crate::models::McpError;
+use super::handlers::HandlerRegistry;
+use super::types::{McpContent, Role};
+
+/// Método JSON-RPC de sampling
+pub const SAMPLING_METHOD: &str = "sampling/createMessage";
+
+/// Código de erro usado quando o usuário rejeita o pedido
+pub const SAMPLING_REJECTED: i32 = -1;
+
+/// Mensagem da conversa enviada ao modelo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    /// Autor da mensagem
+    pub role: Role,
+
+    /// Conteúdo (texto, imagem ou áudio)
+    pub content: McpContent,
+}
+
+impl SamplingMessage {
+    /// Cria uma mensagem de texto do usuário
+    pub fn user_text<S: Into<String>>(text: S) -> Self {
+        Self {
+            role: Role::User,
+            content: McpContent::text(text),
+        }
+    }
+}
+
+/// Sugestão de modelo (nome ou parte do nome)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelHint {
+    /// Nome sugerido
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Preferências do servidor para escolha do modelo
+///
+/// As prioridades vão de 0.0 a 1.0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    /// Modelos sugeridos, em ordem de preferência
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<ModelHint>,
+
+    /// Importância de minimizar custo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f64>,
+
+    /// Importância de baixa latência
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f64>,
+
+    /// Importância de capacidade do modelo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intelligence_priority: Option<f64>,
+}
+
+/// Contexto de servidores MCP a incluir no prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeContext {
+    /// Nenhum contexto
+    None,
+    /// Apenas o servidor que pediu
+    ThisServer,
+    /// Todos os servidores conectados
+    AllServers,
+}
+
+/// Parâmetros de `sampling/createMessage`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageRequest {
+    /// Conversa a completar
+    pub messages: Vec<SamplingMessage>,
+
+    /// Preferências de modelo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+
+    /// Prompt de sistema sugerido
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+
+    /// Contexto a incluir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<IncludeContext>,
+
+    /// Temperatura
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+
+    /// Limite de tokens da resposta
+    pub max_tokens: u32,
+
+    /// Sequências de parada
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+
+    /// Metadados específicos do provedor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Resultado de `sampling/createMessage`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    /// Autor da resposta (normalmente `assistant`)
+    pub role: Role,
+
+    /// Conteúdo gerado
+    pub content: McpContent,
+
+    /// Modelo que gerou a resposta
+    pub model: String,
+
+    /// Motivo da parada (`endTurn`, `stopSequence`, `maxTokens`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+/// Executa as completações pedidas pelo servidor
+#[async_trait]
+pub trait SamplingHandler: Send + Sync {
+    /// Gera a resposta do modelo para a conversa
+    async fn create_message(&self, request: CreateMessageRequest) -> SageXResult<CreateMessageResult>;
+}
+
+/// Decisão da revisão humana
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalDecision<T> {
+    /// Seguir com o valor (possivelmente editado)
+    Approved(T),
+    /// Recusar, com o motivo
+    Rejected(String),
+}
+
+/// Revisão humana dos pedidos de sampling
+#[async_trait]
+pub trait SamplingApproval: Send + Sync {
+    /// Revisa o pedido antes do envio ao modelo
+    async fn review_request(&self, request: CreateMessageRequest) -> ApprovalDecision<CreateMessageRequest>;
+
+    /// Revisa a resposta antes de devolvê-la ao servidor
+    async fn review_result(&self, result: CreateMessageResult) -> ApprovalDecision<CreateMessageResult> {
+        ApprovalDecision::Approved(result)
+    }
+}
+
+/// Adapta um `SamplingHandler` ao registro de handlers
+#[derive(Clone)]
+pub struct SamplingRequestHandler {
+    /// Gerador das respostas
+    handler: Arc<dyn SamplingHandler>,
+
+    /// Revisão humana opcional
+    approval: Option<Arc<dyn SamplingApproval>>,
+}
+
+impl SamplingRequestHandler {
+    /// Cria o adaptador sem revisão humana
+    pub fn new<H: SamplingHandler + 'static>(handler: H) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            approval: None,
+        }
+    }
+
+    /// Exige revisão antes de cada pedido
+    pub fn with_approval<A: SamplingApproval + 'static>(mut self, approval: A) -> Self {
+        self.approval = Some(Arc::new(approval));
+        self
+    }
+
+    /// Registra em `sampling/createMessage`
+    ///
+    /// A capacidade `sampling` passa a ser anunciada no `initialize`.
+    pub fn register(self, registry: &HandlerRegistry) {
+        registry.register_fn(SAMPLING_METHOD, move |request: CreateMessageRequest, _| {
+            let adapter = self.clone();
+            async move { adapter.process(request).await.map_err(SageXError::McpRemote) }
+        });
+    }
+
+    /// Processa um pedido aplicando a revisão, se configurada
+    pub async fn process(&self, request: CreateMessageRequest) -> Result<CreateMessageResult, McpError> {
+        let request = match &self.approval {
+            Some(approval) => match approval.review_request(request).await {
+                ApprovalDecision::Approved(request) => request,
+                ApprovalDecision::Rejected(reason) => return Err(rejected(reason)),
+            },
+            None => request,
+        };
+
+        let result = self.handler.create_message(request).await.map_err(|e| e.to_mcp_error())?;
+
+        match &self.approval {
+            Some(approval) => match approval.review_result(result).await {
+                ApprovalDecision::Approved(result) => Ok(result),
+                ApprovalDecision::Rejected(reason) => Err(rejected(reason)),
+            },
+            None => Ok(result),
+        }
+    }
+}
+
+/// Erro enviado ao servidor quando o usuário recusa o sampling
+fn rejected(reason: String) -> McpError {
+    McpError {
+        code: SAMPLING_REJECTED,
+        message: format!("Sampling recusado pelo usuário: {}", reason),
+        data: None,
+    }
+}
+
+impl fmt::Debug for SamplingRequestHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SamplingRequestHandler")
+            .field("approval", &self.approval.is_some())
+            .finish()
+    }
+}
+
+/// Handler local que responde sem chamar modelo algum
+///
+/// Útil para testes e desenvolvimento offline: devolve o texto configurado
+/// ou, na falta dele, ecoa a última mensagem de texto do usuário.
+#[derive(Debug, Clone, Default)]
+pub struct StubSamplingHandler {
+    /// Resposta fixa
+    response: Option<String>,
+}
+
+impl StubSamplingHandler {
+    /// Nome de modelo reportado pelo stub
+    pub const MODEL: &'static str = "sage-x-stub";
+
+    /// Cria um stub que ecoa o usuário
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Responde sempre com o texto informado
+    pub fn with_response<S: Into<String>>(mut self, response: S) -> Self {
+        self.response = Some(response.into());
+        self
+    }
+}
+
+#[async_trait]
+impl SamplingHandler for StubSamplingHandler {
+    async fn create_message(&self, request: CreateMessageRequest) -> SageXResult<CreateMessageResult> {
+        let text = match &self.response {
+            Some(response) => response.clone(),
+            None => request
+                .messages
+                .iter()
+                .rev()
+                .filter(|message| message.role == Role::User)
+                .find_map(|message| message.content.as_text())
+                .unwrap_or_default()
+                .to_string(),
+        };
+
+        Ok(CreateMessageResult {
+            role: Role::Assistant,
+            content: McpContent::text(text),
+            model: Self::MODEL.to_string(),
+            stop_reason: Some("endTurn".to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> CreateMessageRequest {
+        serde_json::from_value(serde_json::json!({
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Qual a capital da França?"}}
+            ],
+            "modelPreferences": {
+                "hints": [{"name": "claude-3-sonnet"}],
+                "intelligencePriority": 0.8,
+                "speedPriority": 0.5
+            },
+            "systemPrompt": "Você é um assistente útil.",
+            "includeContext": "thisServer",
+            "maxTokens": 100
+        }))
+        .unwrap()
+    }
+
+    struct RejectAll;
+
+    #[async_trait]
+    impl SamplingApproval for RejectAll {
+        async fn review_request(&self, _: CreateMessageRequest) -> ApprovalDecision<CreateMessageRequest> {
+            ApprovalDecision::Rejected("não autorizado".to_string())
+        }
+    }
+
+    struct ShortenPrompt;
+
+    #[async_trait]
+    impl SamplingApproval for ShortenPrompt {
+        async fn review_request(&self, mut request: CreateMessageRequest) -> ApprovalDecision<CreateMessageRequest> {
+            request.messages = vec![SamplingMessage::user_text("editado")];
+            ApprovalDecision::Approved(request)
+        }
+    }
+
+    #[test]
+    fn test_request_parsing() {
+        let request = request();
+        let preferences = request.model_preferences.as_ref().unwrap();
+        assert_eq!(preferences.hints[0].name.as_deref(), Some("claude-3-sonnet"));
+        assert_eq!(preferences.intelligence_priority, Some(0.8));
+        assert_eq!(request.include_context, Some(IncludeContext::ThisServer));
+        assert_eq!(request.max_tokens, 100);
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["maxTokens"], 100);
+        assert!(value.get("stopSequences").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stub_handler_echoes_user() {
+        let handler = SamplingRequestHandler::new(StubSamplingHandler::new());
+        let result = handler.process(request()).await.unwrap();
+
+        assert_eq!(result.role, Role::Assistant);
+        assert_eq!(result.content.as_text(), Some("Qual a capital da França?"));
+        assert_eq!(result.model, StubSamplingHandler::MODEL);
+    }
+
+    #[tokio::test]
+    async fn test_approval_can_reject_or_edit() {
+        let rejecting = SamplingRequestHandler::new(StubSamplingHandler::new()).with_approval(RejectAll);
+        let error = rejecting.process(request()).await.unwrap_err();
+        assert_eq!(error.code, SAMPLING_REJECTED);
+
+        let editing = SamplingRequestHandler::new(StubSamplingHandler::new()).with_approval(ShortenPrompt);
+        let result = editing.process(request()).await.unwrap();
+        assert_eq!(result.content.as_text(), Some("editado"));
+    }
+}
@@ -0,0 +1,144 @@
+//! Assinaturas de resources
+//!
+//! Cada `resources/subscribe` aceito pelo servidor passa a gerar
+//! `notifications/resources/updated`, que são entregues aos streams abertos
+//! para a URI. O registro sobrevive à troca de conexão, permitindo
+//! reassinar após reconectar.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use tokio::sync::mpsc;
+
+use super::types::ResourceUpdated;
+
+/// Destinos das atualizações, por URI
+type Listeners = HashMap<String, Vec<mpsc::UnboundedSender<ResourceUpdated>>>;
+
+/// Registro das URIs assinadas e de seus streams
+///
+/// Clones compartilham o mesmo registro.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSubscriptions {
+    listeners: Arc<Mutex<Listeners>>,
+}
+
+impl ResourceSubscriptions {
+    /// Cria um registro vazio
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abre um stream para a URI; retorna também se é a primeira assinatura dela
+    pub fn add(&self, uri: &str) -> (ResourceSubscription, bool) {
+        let (sender, updates) = mpsc::unbounded_channel();
+        let mut listeners = self.lock();
+        let first = !listeners.contains_key(uri);
+        listeners.entry(uri.to_string()).or_default().push(sender);
+
+        let subscription = ResourceSubscription {
+            uri: uri.to_string(),
+            updates,
+        };
+        (subscription, first)
+    }
+
+    /// Remove a URI, encerrando seus streams; retorna se ela estava assinada
+    pub fn remove(&self, uri: &str) -> bool {
+        self.lock().remove(uri).is_some()
+    }
+
+    /// Verifica se a URI está assinada
+    pub fn contains(&self, uri: &str) -> bool {
+        self.lock().contains_key(uri)
+    }
+
+    /// URIs assinadas
+    pub fn uris(&self) -> Vec<String> {
+        let mut uris: Vec<String> = self.lock().keys().cloned().collect();
+        uris.sort();
+        uris
+    }
+
+    /// Entrega a atualização aos streams abertos da URI
+    ///
+    /// Streams descartados são removidos; a URI continua assinada até
+    /// `remove`, pois a assinatura no servidor só termina com `resources/unsubscribe`.
+    pub fn route(&self, update: &ResourceUpdated) -> usize {
+        let mut listeners = self.lock();
+        let Some(senders) = listeners.get_mut(&update.uri) else {
+            return 0;
+        };
+        senders.retain(|sender| sender.send(update.clone()).is_ok());
+        senders.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Listeners> {
+        self.listeners.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Stream de atualizações de um resource assinado
+///
+/// Termina quando a URI deixa de ser assinada.
+#[derive(Debug)]
+pub struct ResourceSubscription {
+    /// URI assinada
+    uri: String,
+
+    /// Atualizações recebidas
+    updates: mpsc::UnboundedReceiver<ResourceUpdated>,
+}
+
+impl ResourceSubscription {
+    /// URI assinada
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl Stream for ResourceSubscription {
+    type Item = ResourceUpdated;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ResourceUpdated>> {
+        self.updates.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn updated(uri: &str) -> ResourceUpdated {
+        ResourceUpdated {
+            uri: uri.to_string(),
+            title: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_and_remove() {
+        let subscriptions = ResourceSubscriptions::new();
+        let (mut first, is_first) = subscriptions.add("file:///a");
+        let (second, again) = subscriptions.add("file:///a");
+        assert!(is_first);
+        assert!(!again);
+
+        assert_eq!(subscriptions.route(&updated("file:///a")), 2);
+        assert_eq!(subscriptions.route(&updated("file:///b")), 0);
+        assert_eq!(first.next().await.unwrap().uri, "file:///a");
+
+        // Streams descartados deixam de receber, mas a URI segue assinada
+        drop(second);
+        assert_eq!(subscriptions.route(&updated("file:///a")), 1);
+        assert_eq!(subscriptions.uris(), ["file:///a"]);
+
+        assert!(subscriptions.remove("file:///a"));
+        first.next().await.unwrap();
+        assert!(first.next().await.is_none());
+    }
+}
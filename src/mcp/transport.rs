@@ -2,15 +2,34 @@
 //!
 //! Implementa diferentes tipos de transporte para comunicação MCP.
 
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio::sync::{mpsc, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, Command};
+use tokio::sync::{mpsc, Mutex, Notify, RwLock};
+use tokio::net::TcpStream;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_tungstenite::tungstenite::{
+    self,
+    client::IntoClientRequest,
+    http::{HeaderName as WsHeaderName, HeaderValue as WsHeaderValue},
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message as WsMessage,
+};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
 use crate::error::{SageXError, SageXResult};
-use super::messages::McpMessage;
+use crate::models::SageXConfig;
+use super::messages::{McpMessage, McpPayload};
 
 /// Trait para implementações de transporte MCP
 #[async_trait]
@@ -21,9 +40,26 @@ pub trait Transport: Send + Sync + Debug {
     /// Envia uma mensagem
     async fn send_message(&self, message: McpMessage) -> SageXResult<()>;
     
+    /// Envia um lote de mensagens
+    ///
+    /// A implementação padrão envia as mensagens uma a uma; transportes que
+    /// suportam lotes JSON-RPC os enviam como um único array.
+    async fn send_batch(&self, messages: Vec<McpMessage>) -> SageXResult<()> {
+        for message in messages {
+            self.send_message(message).await?;
+        }
+        Ok(())
+    }
+    
     /// Recebe uma mensagem (não-bloqueante)
     async fn receive_message(&self) -> SageXResult<Option<McpMessage>>;
     
+    /// Aguarda a próxima mensagem recebida
+    ///
+    /// Retorna `Ok(None)` quando o transporte se desconecta e não há mais
+    /// mensagens a entregar.
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>>;
+    
     /// Fecha o transporte
     async fn close(&mut self) -> SageXResult<()>;
     
@@ -32,6 +68,20 @@ pub trait Transport: Send + Sync + Debug {
     
     /// Obtém o tipo de transporte
     fn transport_type(&self) -> TransportType;
+    
+    /// Indica se `initialize` pode ser chamado de novo após `close`
+    ///
+    /// Transportes sobre streams consumidos na primeira conexão retornam
+    /// `false` e não são reconectados automaticamente.
+    fn can_reconnect(&self) -> bool {
+        true
+    }
+    
+    /// Registra a versão do protocolo negociada no `initialize`
+    ///
+    /// O Streamable HTTP a envia em `MCP-Protocol-Version` nas requisições
+    /// seguintes; os demais transportes a ignoram.
+    async fn set_protocol_version(&self, _version: &str) {}
 }
 
 /// Tipos de transporte disponíveis
@@ -43,16 +93,38 @@ pub enum TransportType {
     Http,
     /// WebSocket
     WebSocket,
+    /// Processo filho (stdin/stdout do servidor)
+    Process,
     /// Mock (para testes)
     Mock,
 }
 
+/// Estilo de endpoints usado pelo [`HttpTransport`]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMode {
+    /// Endpoints por método (`mcp/request/{method}`) do backend SAGE-X,
+    /// com health check e stream de eventos separados
+    #[default]
+    Legacy,
+    
+    /// Streamable HTTP da especificação MCP: um único endpoint recebe todos
+    /// os POSTs e pode responder com JSON ou com um stream SSE
+    Streamable,
+}
+
 /// Transporte HTTP para MCP
+///
+/// No modo [`HttpMode::Streamable`], `base_url` é o endpoint MCP do servidor;
+/// no modo [`HttpMode::Legacy`], é a raiz da API SAGE-X.
 #[derive(Debug)]
 pub struct HttpTransport {
     /// URL base do servidor
     base_url: String,
     
+    /// Estilo de endpoints
+    mode: HttpMode,
+    
     /// Cliente HTTP
     client: reqwest::Client,
     
@@ -64,31 +136,390 @@ pub struct HttpTransport {
     
     /// Estado da conexão
     connected: Arc<RwLock<bool>>,
+    
+    /// Endpoint do stream SSE no modo legado (desabilitado se `None`)
+    events_endpoint: Option<String>,
+    
+    /// Abre o GET de escuta no endpoint MCP após o primeiro POST (modo Streamable)
+    listen: bool,
+    
+    /// ID do último evento do stream GET, reenviado em `Last-Event-ID` ao reconectar
+    last_event_id: Arc<RwLock<Option<String>>>,
+    
+    /// Sessão atribuída pelo servidor via `Mcp-Session-Id`
+    session_id: Arc<RwLock<Option<String>>>,
+    
+    /// Versão do protocolo negociada, enviada em `MCP-Protocol-Version`
+    protocol_version: Arc<RwLock<Option<String>>>,
+    
+    /// Task de assinatura SSE
+    event_task: Mutex<Option<JoinHandle<()>>>,
+    
+    /// Tasks que consomem respostas SSE de POSTs
+    response_tasks: Mutex<JoinSet<()>>,
 }
 
 impl HttpTransport {
-    /// Cria um novo transporte HTTP
+    /// Endpoint padrão do stream de eventos no modo legado
+    pub const DEFAULT_EVENTS_ENDPOINT: &'static str = "mcp/events";
+    
+    /// Header de sessão do Streamable HTTP
+    pub const SESSION_HEADER: &'static str = "Mcp-Session-Id";
+    
+    /// Header com a versão do protocolo negociada
+    pub const PROTOCOL_VERSION_HEADER: &'static str = "MCP-Protocol-Version";
+    
+    /// Intervalo padrão de reconexão do stream, se o servidor não enviar `retry`
+    const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+    
+    /// Cria um novo transporte HTTP com os endpoints por método do backend SAGE-X
     pub fn new(base_url: String) -> Self {
         let (message_sender, message_receiver) = mpsc::unbounded_channel();
         
         Self {
             base_url,
+            mode: HttpMode::default(),
             client: reqwest::Client::new(),
             incoming_messages: Arc::new(RwLock::new(message_receiver)),
             message_sender,
             connected: Arc::new(RwLock::new(false)),
+            events_endpoint: Some(Self::DEFAULT_EVENTS_ENDPOINT.to_string()),
+            listen: true,
+            last_event_id: Arc::new(RwLock::new(None)),
+            session_id: Arc::new(RwLock::new(None)),
+            protocol_version: Arc::new(RwLock::new(None)),
+            event_task: Mutex::new(None),
+            response_tasks: Mutex::new(JoinSet::new()),
         }
     }
     
+    /// Cria um transporte Streamable HTTP para o endpoint MCP `url`
+    pub fn streamable(url: String) -> Self {
+        Self::new(url).with_mode(HttpMode::Streamable)
+    }
+    
+    /// Define o estilo de endpoints
+    pub fn with_mode(mut self, mode: HttpMode) -> Self {
+        self.mode = mode;
+        self
+    }
+    
+    /// Define o endpoint do stream de eventos (modo legado)
+    pub fn with_event_stream<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.events_endpoint = Some(endpoint.into());
+        self
+    }
+    
+    /// Desabilita o stream de eventos (ou o GET de escuta no modo Streamable)
+    pub fn without_event_stream(mut self) -> Self {
+        self.events_endpoint = None;
+        self.listen = false;
+        self
+    }
+    
+    /// Obtém o estilo de endpoints
+    pub fn mode(&self) -> HttpMode {
+        self.mode
+    }
+    
+    /// Obtém o ID do último evento recebido no stream GET
+    pub async fn last_event_id(&self) -> Option<String> {
+        self.last_event_id.read().await.clone()
+    }
+    
+    /// Obtém a sessão atribuída pelo servidor
+    pub async fn session_id(&self) -> Option<String> {
+        self.session_id.read().await.clone()
+    }
+    
+    /// Obtém a versão do protocolo negociada
+    pub async fn protocol_version(&self) -> Option<String> {
+        self.protocol_version.read().await.clone()
+    }
+    
     /// Constrói URL completa para endpoint
     fn build_url(&self, endpoint: &str) -> String {
         format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'))
     }
+    
+    /// Inicia a assinatura SSE se ainda não estiver ativa
+    async fn start_event_stream(&self, url: String) {
+        let mut event_task = self.event_task.lock().await;
+        if event_task.is_some() {
+            return;
+        }
+        
+        *event_task = Some(tokio::spawn(Self::stream_events(
+            self.client.clone(),
+            url,
+            self.message_sender.clone(),
+            self.connected.clone(),
+            self.last_event_id.clone(),
+            self.session_id.clone(),
+            self.protocol_version.clone(),
+        )));
+    }
+    
+    /// Mantém a assinatura SSE, reconectando enquanto o transporte estiver ativo
+    async fn stream_events(
+        client: reqwest::Client,
+        url: String,
+        message_sender: mpsc::UnboundedSender<McpMessage>,
+        connected: Arc<RwLock<bool>>,
+        last_event_id: Arc<RwLock<Option<String>>>,
+        session_id: Arc<RwLock<Option<String>>>,
+        protocol_version: Arc<RwLock<Option<String>>>,
+    ) {
+        let mut reconnect_delay = Self::DEFAULT_RECONNECT_DELAY;
+        
+        while *connected.read().await {
+            let mut request = client
+                .get(&url)
+                .header(reqwest::header::ACCEPT, "text/event-stream");
+            
+            if let Some(id) = last_event_id.read().await.clone() {
+                request = request.header("Last-Event-ID", id);
+            }
+            if let Some(session) = session_id.read().await.clone() {
+                request = request.header(Self::SESSION_HEADER, session);
+            }
+            if let Some(version) = protocol_version.read().await.clone() {
+                request = request.header(Self::PROTOCOL_VERSION_HEADER, version);
+            }
+            
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    let forwarded = Self::forward_events(response, &message_sender, &last_event_id).await;
+                    match forwarded {
+                        Some(Some(retry)) => reconnect_delay = retry,
+                        Some(None) => {}
+                        None => return,
+                    }
+                }
+                
+                Ok(response) if matches!(
+                    response.status(),
+                    reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED
+                ) => {
+                    log::info!("Servidor não oferece stream de eventos em {}", url);
+                    return;
+                }
+                
+                Ok(response) => {
+                    log::warn!("Stream de eventos retornou status {}", response.status());
+                }
+                
+                Err(e) => {
+                    log::warn!("Falha ao assinar stream de eventos: {}", e);
+                }
+            }
+            
+            tokio::time::sleep(reconnect_delay).await;
+        }
+    }
+    
+    /// Encaminha as mensagens de uma resposta SSE até o fim do stream
+    ///
+    /// Retorna o último `retry` anunciado pelo servidor, ou `None` se o
+    /// receptor de mensagens foi descartado.
+    async fn forward_events(
+        response: reqwest::Response,
+        message_sender: &mpsc::UnboundedSender<McpMessage>,
+        last_event_id: &RwLock<Option<String>>,
+    ) -> Option<Option<Duration>> {
+        let mut retry = None;
+        let mut events = response.bytes_stream().eventsource();
+        
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(event) => {
+                    if event.retry.is_some() {
+                        retry = event.retry;
+                    }
+                    if !event.id.is_empty() {
+                        *last_event_id.write().await = Some(event.id.clone());
+                    }
+                    for message in parse_sse_event(&event) {
+                        if message_sender.send(message).is_err() {
+                            return None;
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Stream de eventos interrompido: {}", e);
+                    break;
+                }
+            }
+        }
+        
+        Some(retry)
+    }
+    
+    /// Envia uma mensagem no estilo de endpoints por método
+    async fn send_legacy(&self, message: McpMessage) -> SageXResult<()> {
+        let endpoint = match &message {
+            McpMessage::Request(req) => format!("mcp/request/{}", req.method),
+            McpMessage::Response(resp) => match &resp.id {
+                Some(id) => format!("mcp/response/{}", id),
+                None => "mcp/response/null".to_string(),
+            },
+            McpMessage::Notification(notif) => format!("mcp/notification/{}", notif.method),
+        };
+        
+        let url = self.build_url(&endpoint);
+        
+        let response = self.client
+            .post(&url)
+            .json(&message)
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| SageXError::connection(format!("Falha ao enviar mensagem: {}", e)))?;
+        
+        if !response.status().is_success() {
+            return Err(SageXError::Http(format!(
+                "Erro HTTP {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Envia uma mensagem ao endpoint único do Streamable HTTP
+    async fn send_streamable(&self, payload: McpPayload) -> SageXResult<()> {
+        let initializing = matches!(&payload, McpPayload::Single(message) if message.method() == Some("initialize"));
+        let mut request = self.client
+            .post(&self.base_url)
+            .header(reqwest::header::ACCEPT, "application/json, text/event-stream")
+            .json(&payload);
+        
+        let current_session = self.session_id.read().await.clone();
+        if let Some(session) = &current_session {
+            request = request.header(Self::SESSION_HEADER, session);
+        }
+        if let Some(version) = self.protocol_version.read().await.clone() {
+            request = request.header(Self::PROTOCOL_VERSION_HEADER, version);
+        }
+        
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SageXError::connection(format!("Falha ao enviar mensagem: {}", e)))?;
+        
+        if let Some(session) = response.headers().get(Self::SESSION_HEADER) {
+            let session = session.to_str()
+                .map_err(|e| SageXError::mcp_protocol(format!("{} inválido: {}", Self::SESSION_HEADER, e)))?;
+            *self.session_id.write().await = Some(session.to_string());
+        }
+        
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND && current_session.is_some() {
+            // Sessão expirada: o cliente precisa reinicializar
+            *self.session_id.write().await = None;
+            return Err(SageXError::connection("Sessão MCP expirada no servidor"));
+        }
+        
+        if !status.is_success() {
+            return Err(SageXError::Http(format!(
+                "Erro HTTP {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            )));
+        }
+        
+        // Após o initialize, escutar mensagens iniciadas pelo servidor; o GET
+        // já leva a versão negociada
+        if self.listen && !initializing {
+            self.start_event_stream(self.base_url.clone()).await;
+        }
+        
+        if status == reqwest::StatusCode::ACCEPTED {
+            return Ok(());
+        }
+        
+        let content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        
+        if content_type.starts_with("text/event-stream") {
+            // A resposta pode incluir notificações de progresso antes do resultado,
+            // então o stream é consumido em background. Os IDs de evento valem
+            // apenas dentro deste stream e não afetam a retomada do GET.
+            let message_sender = self.message_sender.clone();
+            let mut response_tasks = self.response_tasks.lock().await;
+            while response_tasks.try_join_next().is_some() {}
+            response_tasks.spawn(async move {
+                let last_event_id = RwLock::new(None);
+                Self::forward_events(response, &message_sender, &last_event_id).await;
+            });
+        } else if content_type.starts_with("application/json") {
+            let body = response.bytes().await
+                .map_err(|e| SageXError::connection(format!("Falha ao ler resposta: {}", e)))?;
+            if !body.is_empty() {
+                let payload: McpPayload = serde_json::from_slice(&body)
+                    .map_err(|e| SageXError::serialization(format!("Resposta MCP inválida: {}", e)))?;
+                for message in payload.into_messages() {
+                    let _ = self.message_sender.send(message);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Encerra a sessão no servidor
+    async fn terminate_session(&self) {
+        if let Some(session) = self.session_id.write().await.take() {
+            let mut request = self.client
+                .delete(&self.base_url)
+                .header(Self::SESSION_HEADER, session)
+                .timeout(Duration::from_secs(10));
+            if let Some(version) = self.protocol_version.read().await.clone() {
+                request = request.header(Self::PROTOCOL_VERSION_HEADER, version);
+            }
+            
+            let result = request.send().await;
+            
+            match result {
+                Ok(response) if response.status().is_success()
+                    || response.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED => {}
+                Ok(response) => log::warn!("Encerramento de sessão retornou status {}", response.status()),
+                Err(e) => log::warn!("Falha ao encerrar sessão MCP: {}", e),
+            }
+        }
+    }
+}
+
+/// Converte um evento SSE em mensagens MCP
+///
+/// Apenas eventos sem nome ou do tipo `message` carregam mensagens.
+fn parse_sse_event(event: &eventsource_stream::Event) -> Vec<McpMessage> {
+    if !(event.event.is_empty() || event.event == "message") || event.data.trim().is_empty() {
+        return Vec::new();
+    }
+    
+    match serde_json::from_str::<McpPayload>(&event.data) {
+        Ok(payload) => payload.into_messages(),
+        Err(e) => {
+            log::warn!("Evento SSE inválido ignorado: {}", e);
+            Vec::new()
+        }
+    }
 }
 
 #[async_trait]
 impl Transport for HttpTransport {
     async fn initialize(&mut self) -> SageXResult<()> {
+        if self.mode == HttpMode::Streamable {
+            // O endpoint único não tem health check; a sessão nasce no `initialize` MCP
+            *self.connected.write().await = true;
+            return Ok(());
+        }
+        
         // Testar conectividade com endpoint de health
         let health_url = self.build_url("health");
         
@@ -100,8 +531,15 @@ impl Transport for HttpTransport {
             .map_err(|e| SageXError::connection(format!("Falha ao conectar com {}: {}", health_url, e)))?;
         
         if response.status().is_success() {
-            let mut connected = self.connected.write().await;
-            *connected = true;
+            {
+                let mut connected = self.connected.write().await;
+                *connected = true;
+            }
+            
+            if let Some(endpoint) = &self.events_endpoint {
+                self.start_event_stream(self.build_url(endpoint)).await;
+            }
+            
             Ok(())
         } else {
             Err(SageXError::connection(format!(
@@ -111,122 +549,829 @@ impl Transport for HttpTransport {
         }
     }
     
-    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
-        if !self.is_connected().await {
-            return Err(SageXError::connection("Transporte não conectado"));
-        }
-        
-        let endpoint = match &message {
-            McpMessage::Request(req) => format!("mcp/request/{}", req.method),
-            McpMessage::Response(resp) => format!("mcp/response/{}", resp.id),
-            McpMessage::Notification(notif) => format!("mcp/notification/{}", notif.method),
-        };
-        
-        let url = self.build_url(&endpoint);
-        
-        let response = self.client
-            .post(&url)
-            .json(&message)
-            .timeout(std::time::Duration::from_secs(30))
-            .send()
-            .await
-            .map_err(|e| SageXError::connection(format!("Falha ao enviar mensagem: {}", e)))?;
-        
-        if !response.status().is_success() {
-            return Err(SageXError::Http(format!(
-                "Erro HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+        if !self.is_connected().await {
+            return Err(SageXError::connection("Transporte não conectado"));
+        }
+        
+        match self.mode {
+            HttpMode::Streamable => self.send_streamable(message.into()).await,
+            HttpMode::Legacy => self.send_legacy(message).await,
+        }
+    }
+    
+    async fn send_batch(&self, messages: Vec<McpMessage>) -> SageXResult<()> {
+        if !self.is_connected().await {
+            return Err(SageXError::connection("Transporte não conectado"));
+        }
+        
+        match self.mode {
+            HttpMode::Streamable => self.send_streamable(messages.into()).await,
+            HttpMode::Legacy => {
+                for message in messages {
+                    self.send_legacy(message).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+    
+    async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
+        let mut incoming = self.incoming_messages.write().await;
+        Ok(incoming.try_recv().ok())
+    }
+    
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+        // O transporte mantém um sender, então o canal só termina em `close`
+        if !self.is_connected().await {
+            return Ok(None);
+        }
+        Ok(self.incoming_messages.write().await.recv().await)
+    }
+    
+    async fn close(&mut self) -> SageXResult<()> {
+        if let Some(task) = self.event_task.get_mut().take() {
+            task.abort();
+        }
+        self.response_tasks.get_mut().abort_all();
+        
+        if self.mode == HttpMode::Streamable {
+            self.terminate_session().await;
+        }
+        // Uma nova sessão renegocia a versão
+        *self.protocol_version.write().await = None;
+        
+        let mut connected = self.connected.write().await;
+        *connected = false;
+        Ok(())
+    }
+    
+    async fn is_connected(&self) -> bool {
+        *self.connected.read().await
+    }
+    
+    fn transport_type(&self) -> TransportType {
+        TransportType::Http
+    }
+    
+    async fn set_protocol_version(&self, version: &str) {
+        *self.protocol_version.write().await = Some(version.to_string());
+    }
+}
+
+/// Leitor assíncrono usado pelos transportes baseados em streams
+pub type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Escritor assíncrono usado pelos transportes baseados em streams
+pub type BoxedWriter = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// Transporte Standard I/O para MCP
+///
+/// Usa JSON delimitado por nova linha: cada linha contém exatamente uma
+/// mensagem JSON-RPC serializada.
+pub struct StdioTransport {
+    /// Canal para mensagens recebidas; o sender fica com a task de leitura,
+    /// e o canal se encerra no EOF
+    incoming_messages: Arc<RwLock<mpsc::UnboundedReceiver<McpMessage>>>,
+    
+    /// Estado da conexão
+    connected: Arc<RwLock<bool>>,
+    
+    /// Stream de entrada, consumido pela task de leitura em `initialize`
+    reader: Mutex<Option<BoxedReader>>,
+    
+    /// Stream de saída, com acesso exclusivo por mensagem
+    writer: Arc<Mutex<BoxedWriter>>,
+    
+    /// Task de leitura em background
+    reader_task: Option<JoinHandle<()>>,
+}
+
+impl StdioTransport {
+    /// Cria um novo transporte Stdio sobre stdin/stdout do processo
+    pub fn new() -> Self {
+        Self::with_streams(Box::new(tokio::io::stdin()), Box::new(tokio::io::stdout()))
+    }
+    
+    /// Cria um transporte Stdio sobre streams arbitrários
+    pub fn with_streams(reader: BoxedReader, writer: BoxedWriter) -> Self {
+        let (_, message_receiver) = mpsc::unbounded_channel();
+        
+        Self {
+            incoming_messages: Arc::new(RwLock::new(message_receiver)),
+            connected: Arc::new(RwLock::new(false)),
+            reader: Mutex::new(Some(reader)),
+            writer: Arc::new(Mutex::new(writer)),
+            reader_task: None,
+        }
+    }
+    
+    /// Lê linhas do stream e as encaminha como mensagens até EOF
+    async fn read_lines(
+        reader: BoxedReader,
+        message_sender: mpsc::UnboundedSender<McpMessage>,
+        connected: Arc<RwLock<bool>>,
+    ) {
+        let mut lines = BufReader::new(reader).lines();
+        
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    
+                    match serde_json::from_str::<McpPayload>(line) {
+                        Ok(payload) => {
+                            if payload.into_messages().into_iter().any(|m| message_sender.send(m).is_err()) {
+                                break;
+                            }
+                        }
+                        Err(e) => log::warn!("Linha inválida ignorada no transporte stdio: {}", e),
+                    }
+                }
+                Ok(None) => {
+                    log::info!("EOF no transporte stdio");
+                    break;
+                }
+                Err(e) => {
+                    log::error!("Erro de leitura no transporte stdio: {}", e);
+                    break;
+                }
+            }
+        }
+        
+        let mut connected = connected.write().await;
+        *connected = false;
+    }
+}
+
+impl StdioTransport {
+    /// Serializa o payload e o escreve como uma única linha
+    async fn write_payload(&self, payload: &McpPayload) -> SageXResult<()> {
+        if !self.is_connected().await {
+            return Err(SageXError::connection("Transporte não conectado"));
+        }
+        
+        let mut json = serde_json::to_string(payload)
+            .map_err(|e| SageXError::serialization(format!("Falha ao serializar mensagem: {}", e)))?;
+        json.push('\n');
+        
+        let mut writer = self.writer.lock().await;
+        writer.write_all(json.as_bytes()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+}
+
+impl Default for StdioTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for StdioTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StdioTransport")
+            .field("connected", &self.connected)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn initialize(&mut self) -> SageXResult<()> {
+        let reader = self.reader.get_mut().take()
+            .ok_or_else(|| SageXError::connection("Stream de entrada já consumido"))?;
+        
+        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+        *self.incoming_messages.write().await = message_receiver;
+        
+        {
+            let mut connected = self.connected.write().await;
+            *connected = true;
+        }
+        
+        self.reader_task = Some(tokio::spawn(Self::read_lines(
+            reader,
+            message_sender,
+            self.connected.clone(),
+        )));
+        
+        Ok(())
+    }
+    
+    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+        self.write_payload(&message.into()).await
+    }
+    
+    async fn send_batch(&self, messages: Vec<McpMessage>) -> SageXResult<()> {
+        self.write_payload(&messages.into()).await
+    }
+    
+    async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
+        let mut incoming = self.incoming_messages.write().await;
+        Ok(incoming.try_recv().ok())
+    }
+    
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+        Ok(self.incoming_messages.write().await.recv().await)
+    }
+    
+    async fn close(&mut self) -> SageXResult<()> {
+        if let Some(task) = self.reader_task.take() {
+            task.abort();
+        }
+        
+        {
+            let mut connected = self.connected.write().await;
+            *connected = false;
+        }
+        
+        self.writer.lock().await.flush().await?;
+        Ok(())
+    }
+    
+    fn can_reconnect(&self) -> bool {
+        false
+    }
+    
+    async fn is_connected(&self) -> bool {
+        *self.connected.read().await
+    }
+    
+    fn transport_type(&self) -> TransportType {
+        TransportType::Stdio
+    }
+}
+
+/// Configuração de um servidor MCP lançado como processo filho
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessConfig {
+    /// Comando a ser executado (ex.: `npx`, `python`)
+    pub command: String,
+    
+    /// Argumentos do comando
+    #[serde(default)]
+    pub args: Vec<String>,
+    
+    /// Variáveis de ambiente adicionais
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    
+    /// Diretório de trabalho do processo
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+impl ProcessConfig {
+    /// Cria uma configuração para o comando informado
+    pub fn new<S: Into<String>>(command: S) -> Self {
+        Self {
+            command: command.into(),
+            ..Default::default()
+        }
+    }
+    
+    /// Adiciona argumentos ao comando
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+    
+    /// Define uma variável de ambiente
+    pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+    
+    /// Define o diretório de trabalho
+    pub fn with_cwd<S: Into<String>>(mut self, cwd: S) -> Self {
+        self.cwd = Some(cwd.into());
+        self
+    }
+}
+
+/// Transporte que lança um servidor MCP como processo filho
+///
+/// As mensagens trafegam pelo stdin/stdout do processo com o mesmo
+/// enquadramento do [`StdioTransport`]; o stderr é encaminhado para o `log`.
+#[derive(Debug)]
+pub struct ProcessTransport {
+    /// Configuração do processo
+    config: ProcessConfig,
+    
+    /// Processo filho em execução
+    child: Option<Child>,
+    
+    /// Transporte sobre os pipes do processo
+    stdio: Option<StdioTransport>,
+    
+    /// Task que encaminha o stderr do processo
+    stderr_task: Option<JoinHandle<()>>,
+}
+
+impl ProcessTransport {
+    /// Tempo de espera pelo término voluntário do processo antes de matá-lo
+    const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+    
+    /// Cria um novo transporte de processo
+    pub fn new(config: ProcessConfig) -> Self {
+        Self {
+            config,
+            child: None,
+            stdio: None,
+            stderr_task: None,
+        }
+    }
+    
+    /// Obtém o PID do processo filho, se estiver em execução
+    pub fn pid(&self) -> Option<u32> {
+        self.child.as_ref().and_then(|child| child.id())
+    }
+    
+    /// Encaminha cada linha do stderr do processo para o `log`
+    async fn forward_stderr(stderr: ChildStderr, command: String) {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            log::warn!(target: "sage_x_mcp_client::process", "[{}] {}", command, line);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ProcessTransport {
+    async fn initialize(&mut self) -> SageXResult<()> {
+        if self.child.is_some() {
+            return Err(SageXError::connection("Processo já iniciado"));
+        }
+        
+        let mut command = Command::new(&self.config.command);
+        command
+            .args(&self.config.args)
+            .envs(&self.config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        
+        if let Some(cwd) = &self.config.cwd {
+            command.current_dir(cwd);
+        }
+        
+        let mut child = command.spawn().map_err(|e| {
+            SageXError::connection(format!("Falha ao iniciar '{}': {}", self.config.command, e))
+        })?;
+        
+        let stdin = child.stdin.take()
+            .ok_or_else(|| SageXError::connection("stdin do processo indisponível"))?;
+        let stdout = child.stdout.take()
+            .ok_or_else(|| SageXError::connection("stdout do processo indisponível"))?;
+        
+        if let Some(stderr) = child.stderr.take() {
+            self.stderr_task = Some(tokio::spawn(Self::forward_stderr(
+                stderr,
+                self.config.command.clone(),
+            )));
+        }
+        
+        let mut stdio = StdioTransport::with_streams(Box::new(stdout), Box::new(stdin));
+        stdio.initialize().await?;
+        
+        self.stdio = Some(stdio);
+        self.child = Some(child);
+        Ok(())
+    }
+    
+    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+        match &self.stdio {
+            Some(stdio) => stdio.send_message(message).await,
+            None => Err(SageXError::connection("Transporte não conectado")),
+        }
+    }
+    
+    async fn send_batch(&self, messages: Vec<McpMessage>) -> SageXResult<()> {
+        match &self.stdio {
+            Some(stdio) => stdio.send_batch(messages).await,
+            None => Err(SageXError::connection("Transporte não conectado")),
+        }
+    }
+    
+    async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
+        match &self.stdio {
+            Some(stdio) => stdio.receive_message().await,
+            None => Ok(None),
+        }
+    }
+    
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+        match &self.stdio {
+            Some(stdio) => stdio.next_message().await,
+            None => Ok(None),
+        }
+    }
+    
+    async fn close(&mut self) -> SageXResult<()> {
+        // Descartar o transporte fecha o stdin, sinalizando o fim ao processo
+        if let Some(mut stdio) = self.stdio.take() {
+            let _ = stdio.close().await;
+        }
+        
+        if let Some(mut child) = self.child.take() {
+            match tokio::time::timeout(Self::SHUTDOWN_GRACE, child.wait()).await {
+                Ok(status) => {
+                    log::debug!("Processo '{}' terminou: {:?}", self.config.command, status);
+                }
+                Err(_) => {
+                    child.kill().await?;
+                }
+            }
+        }
+        
+        if let Some(task) = self.stderr_task.take() {
+            task.abort();
+        }
+        
+        Ok(())
+    }
+    
+    async fn is_connected(&self) -> bool {
+        match &self.stdio {
+            Some(stdio) => stdio.is_connected().await,
+            None => false,
+        }
+    }
+    
+    fn transport_type(&self) -> TransportType {
+        TransportType::Process
+    }
+}
+
+/// Stream WebSocket do lado cliente
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serializa durações como milissegundos inteiros
+mod duration_ms {
+    use std::time::Duration;
+    
+    use serde::{Deserialize, Deserializer, Serializer};
+    
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+    
+    /// Variante para durações opcionais (`null` desabilita)
+    pub mod option {
+        use super::*;
+        
+        pub fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => super::serialize(duration, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+        
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+            Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+        }
+    }
+}
+
+/// Configuração do transporte WebSocket
+///
+/// Em JSON, as durações são informadas em milissegundos
+/// (`ping_interval_ms`, `connect_timeout_ms`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketConfig {
+    /// URL do servidor (`ws://` ou `wss://`)
+    pub url: String,
+    
+    /// Subprotocolo anunciado em `Sec-WebSocket-Protocol`
+    pub subprotocol: Option<String>,
+    
+    /// Headers adicionais do handshake
+    pub headers: HashMap<String, String>,
+    
+    /// Token enviado como `Authorization: Bearer`
+    pub bearer_token: Option<String>,
+    
+    /// Intervalo entre pings de keepalive (desabilitado se `None`)
+    #[serde(rename = "ping_interval_ms", with = "duration_ms::option")]
+    pub ping_interval: Option<Duration>,
+    
+    /// Timeout do handshake
+    #[serde(rename = "connect_timeout_ms", with = "duration_ms")]
+    pub connect_timeout: Duration,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            url: "ws://localhost:8080".to_string(),
+            subprotocol: Some("mcp".to_string()),
+            headers: HashMap::new(),
+            bearer_token: None,
+            ping_interval: Some(Duration::from_secs(30)),
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// Cria uma configuração para a URL informada
+    pub fn new<S: Into<String>>(url: S) -> Self {
+        Self {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+    
+    /// Cria uma configuração herdando token, headers e timeout do cliente
+    pub fn from_sage_config<S: Into<String>>(url: S, config: &SageXConfig) -> Self {
+        Self {
+            url: url.into(),
+            headers: config.network.custom_headers.clone(),
+            bearer_token: Some(config.auth_token.clone()).filter(|token| !token.is_empty()),
+            connect_timeout: config.network.connect_timeout,
+            ..Default::default()
         }
-        
-        Ok(())
     }
     
-    async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
-        let mut incoming = self.incoming_messages.write().await;
-        Ok(incoming.try_recv().ok())
+    /// Define o subprotocolo
+    pub fn with_subprotocol<S: Into<String>>(mut self, subprotocol: S) -> Self {
+        self.subprotocol = Some(subprotocol.into());
+        self
     }
     
-    async fn close(&mut self) -> SageXResult<()> {
-        let mut connected = self.connected.write().await;
-        *connected = false;
-        Ok(())
+    /// Adiciona um header ao handshake
+    pub fn with_header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
     }
     
-    async fn is_connected(&self) -> bool {
-        *self.connected.read().await
+    /// Define o token de autenticação
+    pub fn with_bearer_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.bearer_token = Some(token.into());
+        self
     }
     
-    fn transport_type(&self) -> TransportType {
-        TransportType::Http
+    /// Define o intervalo de keepalive
+    pub fn with_ping_interval(mut self, interval: Option<Duration>) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+    
+    /// Monta o request de handshake com os headers configurados
+    fn build_request(&self) -> SageXResult<tungstenite::handshake::client::Request> {
+        let mut request = self.url.as_str().into_client_request()
+            .map_err(|e| SageXError::configuration(format!("URL WebSocket inválida '{}': {}", self.url, e)))?;
+        
+        let headers = request.headers_mut();
+        
+        if let Some(subprotocol) = &self.subprotocol {
+            headers.insert(
+                "Sec-WebSocket-Protocol",
+                WsHeaderValue::from_str(subprotocol)
+                    .map_err(|e| SageXError::configuration(format!("Subprotocolo inválido: {}", e)))?,
+            );
+        }
+        
+        if let Some(token) = &self.bearer_token {
+            headers.insert(
+                "Authorization",
+                WsHeaderValue::from_str(&format!("Bearer {}", token))
+                    .map_err(|e| SageXError::authentication(format!("Token inválido: {}", e)))?,
+            );
+        }
+        
+        for (key, value) in &self.headers {
+            let header_name: WsHeaderName = key.parse()
+                .map_err(|e| SageXError::configuration(format!("Header inválido '{}': {}", key, e)))?;
+            let header_value = WsHeaderValue::from_str(value)
+                .map_err(|e| SageXError::configuration(format!("Valor de header inválido '{}': {}", value, e)))?;
+            headers.insert(header_name, header_value);
+        }
+        
+        Ok(request)
     }
 }
 
-/// Transporte Standard I/O para MCP
+/// Transporte WebSocket para MCP
+///
+/// Cada mensagem JSON-RPC trafega em um frame de texto. Um fechamento normal
+/// é reportado como desconexão; fechamentos anormais e falhas de keepalive
+/// são reportados como erro em `receive_message`.
 #[derive(Debug)]
-pub struct StdioTransport {
-    /// Canal para mensagens recebidas
-    incoming_messages: Arc<RwLock<mpsc::UnboundedReceiver<McpMessage>>>,
+pub struct WebSocketTransport {
+    /// Configuração do transporte
+    config: WebSocketConfig,
     
-    /// Sender para mensagens recebidas
-    message_sender: mpsc::UnboundedSender<McpMessage>,
+    /// Metade de escrita do stream
+    sink: Arc<Mutex<Option<SplitSink<WsStream, WsMessage>>>>,
+    
+    /// Canal para mensagens recebidas; o sender fica com a task de leitura,
+    /// e o canal se encerra quando o stream termina
+    incoming_messages: Arc<RwLock<mpsc::UnboundedReceiver<McpMessage>>>,
     
     /// Estado da conexão
     connected: Arc<RwLock<bool>>,
+    
+    /// Motivo de um fechamento anormal, reportado uma única vez
+    close_error: Arc<RwLock<Option<String>>>,
+    
+    /// Task de leitura e keepalive
+    reader_task: Option<JoinHandle<()>>,
 }
 
-impl StdioTransport {
-    /// Cria um novo transporte Stdio
-    pub fn new() -> Self {
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+impl WebSocketTransport {
+    /// Cria um novo transporte WebSocket
+    pub fn new(config: WebSocketConfig) -> Self {
+        let (_, message_receiver) = mpsc::unbounded_channel();
         
         Self {
+            config,
+            sink: Arc::new(Mutex::new(None)),
             incoming_messages: Arc::new(RwLock::new(message_receiver)),
-            message_sender,
             connected: Arc::new(RwLock::new(false)),
+            close_error: Arc::new(RwLock::new(None)),
+            reader_task: None,
         }
     }
-}
-
-impl Default for StdioTransport {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait]
-impl Transport for StdioTransport {
-    async fn initialize(&mut self) -> SageXResult<()> {
-        // Para stdio, apenas marcar como conectado
-        let mut connected = self.connected.write().await;
-        *connected = true;
+    
+    /// Lê frames do servidor e envia pings de keepalive
+    async fn read_frames(
+        mut stream: SplitStream<WsStream>,
+        sink: Arc<Mutex<Option<SplitSink<WsStream, WsMessage>>>>,
+        message_sender: mpsc::UnboundedSender<McpMessage>,
+        connected: Arc<RwLock<bool>>,
+        close_error: Arc<RwLock<Option<String>>>,
+        ping_interval: Option<Duration>,
+    ) {
+        let mut keepalive = tokio::time::interval(ping_interval.unwrap_or(Duration::from_secs(3600)));
+        keepalive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        keepalive.tick().await;
+        let mut awaiting_pong = false;
         
-        // TODO: Iniciar task para ler de stdin em background
-        Ok(())
+        let failure = loop {
+            tokio::select! {
+                frame = stream.next() => match frame {
+                    Some(Ok(WsMessage::Text(text))) => Self::dispatch_frame(text.as_bytes(), &message_sender),
+                    Some(Ok(WsMessage::Binary(data))) => Self::dispatch_frame(&data, &message_sender),
+                    Some(Ok(WsMessage::Pong(_))) => awaiting_pong = false,
+                    Some(Ok(WsMessage::Ping(_))) | Some(Ok(WsMessage::Frame(_))) => {}
+                    Some(Ok(WsMessage::Close(frame))) => {
+                        break match frame {
+                            Some(frame) if frame.code != CloseCode::Normal && frame.code != CloseCode::Away => {
+                                Some(format!("WebSocket fechado pelo servidor ({}): {}", frame.code, frame.reason))
+                            }
+                            _ => None,
+                        };
+                    }
+                    Some(Err(e)) => break Some(format!("Erro no WebSocket: {}", e)),
+                    None => break Some("WebSocket encerrado sem frame de fechamento".to_string()),
+                },
+                
+                _ = keepalive.tick(), if ping_interval.is_some() => {
+                    if awaiting_pong {
+                        break Some("Keepalive sem resposta do servidor".to_string());
+                    }
+                    
+                    let mut sink = sink.lock().await;
+                    if let Some(sink) = sink.as_mut() {
+                        if let Err(e) = sink.send(WsMessage::Ping(Vec::new())).await {
+                            break Some(format!("Falha ao enviar ping: {}", e));
+                        }
+                    }
+                    awaiting_pong = true;
+                }
+            }
+        };
+        
+        if let Some(reason) = failure {
+            log::warn!("{}", reason);
+            *close_error.write().await = Some(reason);
+        }
+        
+        *connected.write().await = false;
     }
     
-    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+    /// Envia o payload em um frame de texto
+    async fn send_payload(&self, payload: &McpPayload) -> SageXResult<()> {
         if !self.is_connected().await {
             return Err(SageXError::connection("Transporte não conectado"));
         }
         
-        // Serializar e enviar para stdout
-        let json = serde_json::to_string(&message)
+        let json = serde_json::to_string(payload)
             .map_err(|e| SageXError::serialization(format!("Falha ao serializar mensagem: {}", e)))?;
         
-        println!("{}", json);
+        let mut sink = self.sink.lock().await;
+        let sink = sink.as_mut()
+            .ok_or_else(|| SageXError::connection("Transporte não conectado"))?;
+        sink.send(WsMessage::Text(json)).await
+            .map_err(|e| SageXError::connection(format!("Falha ao enviar mensagem: {}", e)))
+    }
+    
+    /// Converte o payload de um frame em mensagens MCP
+    fn dispatch_frame(payload: &[u8], message_sender: &mpsc::UnboundedSender<McpMessage>) {
+        match serde_json::from_slice::<McpPayload>(payload) {
+            Ok(payload) => {
+                for message in payload.into_messages() {
+                    let _ = message_sender.send(message);
+                }
+            }
+            Err(e) => log::warn!("Frame inválido ignorado no transporte WebSocket: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn initialize(&mut self) -> SageXResult<()> {
+        let request = self.config.build_request()?;
+        
+        let (stream, _response) = tokio::time::timeout(
+            self.config.connect_timeout,
+            connect_async(request),
+        )
+        .await
+        .map_err(|_| SageXError::timeout_after(self.config.connect_timeout, "Handshake WebSocket"))?
+        .map_err(|e| SageXError::connection(format!("Falha ao conectar com {}: {}", self.config.url, e)))?;
+        
+        let (sink, stream) = stream.split();
+        let (message_sender, message_receiver) = mpsc::unbounded_channel();
+        *self.sink.lock().await = Some(sink);
+        *self.incoming_messages.write().await = message_receiver;
+        *self.close_error.write().await = None;
+        *self.connected.write().await = true;
+        
+        self.reader_task = Some(tokio::spawn(Self::read_frames(
+            stream,
+            self.sink.clone(),
+            message_sender,
+            self.connected.clone(),
+            self.close_error.clone(),
+            self.config.ping_interval,
+        )));
+        
         Ok(())
     }
     
+    async fn send_message(&self, message: McpMessage) -> SageXResult<()> {
+        self.send_payload(&message.into()).await
+    }
+    
+    async fn send_batch(&self, messages: Vec<McpMessage>) -> SageXResult<()> {
+        self.send_payload(&messages.into()).await
+    }
+    
     async fn receive_message(&self) -> SageXResult<Option<McpMessage>> {
         let mut incoming = self.incoming_messages.write().await;
-        Ok(incoming.try_recv().ok())
+        if let Ok(message) = incoming.try_recv() {
+            return Ok(Some(message));
+        }
+        
+        match self.close_error.write().await.take() {
+            Some(reason) => Err(SageXError::connection(reason)),
+            None => Ok(None),
+        }
+    }
+    
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+        if let Some(message) = self.incoming_messages.write().await.recv().await {
+            return Ok(Some(message));
+        }
+        
+        match self.close_error.write().await.take() {
+            Some(reason) => Err(SageXError::connection(reason)),
+            None => Ok(None),
+        }
     }
     
     async fn close(&mut self) -> SageXResult<()> {
-        let mut connected = self.connected.write().await;
-        *connected = false;
+        if let Some(mut sink) = self.sink.lock().await.take() {
+            let close_frame = CloseFrame {
+                code: CloseCode::Normal,
+                reason: "".into(),
+            };
+            let _ = sink.send(WsMessage::Close(Some(close_frame))).await;
+            let _ = sink.close().await;
+        }
+        
+        if let Some(task) = self.reader_task.take() {
+            task.abort();
+        }
+        
+        *self.connected.write().await = false;
         Ok(())
     }
     
@@ -235,12 +1380,15 @@ impl Transport for StdioTransport {
     }
     
     fn transport_type(&self) -> TransportType {
-        TransportType::Stdio
+        TransportType::WebSocket
     }
 }
 
 /// Transporte Mock para testes
-#[derive(Debug)]
+///
+/// Clones compartilham as mesmas filas, permitindo inspecionar o transporte
+/// depois de entregá-lo a uma conexão.
+#[derive(Debug, Clone)]
 pub struct MockTransport {
     /// Mensagens enviadas (para verificação em testes)
     sent_messages: Arc<RwLock<Vec<McpMessage>>>,
@@ -248,6 +1396,9 @@ pub struct MockTransport {
     /// Mensagens a serem recebidas (simuladas)
     mock_incoming: Arc<RwLock<Vec<McpMessage>>>,
     
+    /// Acorda `next_message` quando chega mensagem ou o mock é fechado
+    incoming_ready: Arc<Notify>,
+    
     /// Estado da conexão
     connected: Arc<RwLock<bool>>,
     
@@ -264,6 +1415,7 @@ impl MockTransport {
         Self {
             sent_messages: Arc::new(RwLock::new(Vec::new())),
             mock_incoming: Arc::new(RwLock::new(Vec::new())),
+            incoming_ready: Arc::new(Notify::new()),
             connected: Arc::new(RwLock::new(false)),
             fail_init: false,
             fail_send: false,
@@ -286,6 +1438,7 @@ impl MockTransport {
     pub async fn add_incoming_message(&self, message: McpMessage) {
         let mut incoming = self.mock_incoming.write().await;
         incoming.push(message);
+        self.incoming_ready.notify_one();
     }
     
     /// Obtém todas as mensagens enviadas
@@ -337,9 +1490,23 @@ impl Transport for MockTransport {
         Ok(incoming.pop())
     }
     
+    async fn next_message(&self) -> SageXResult<Option<McpMessage>> {
+        loop {
+            if let Some(message) = self.receive_message().await? {
+                return Ok(Some(message));
+            }
+            if !self.is_connected().await {
+                return Ok(None);
+            }
+            // `notify_one` guarda a permissão se ninguém estiver esperando
+            self.incoming_ready.notified().await;
+        }
+    }
+    
     async fn close(&mut self) -> SageXResult<()> {
         let mut connected = self.connected.write().await;
         *connected = false;
+        self.incoming_ready.notify_one();
         Ok(())
     }
     
@@ -360,25 +1527,42 @@ impl TransportFactory {
     pub fn create(transport_type: TransportType, config: Option<serde_json::Value>) -> SageXResult<Box<dyn Transport>> {
         match transport_type {
             TransportType::Http => {
-                let base_url = if let Some(config) = config {
-                    config.get("base_url")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("http://localhost:8080")
-                        .to_string()
-                } else {
-                    "http://localhost:8080".to_string()
+                let base_url = config.as_ref()
+                    .and_then(|config| config.get("base_url"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("http://localhost:8080")
+                    .to_string();
+                
+                let mode = match config.as_ref().and_then(|config| config.get("mode")) {
+                    Some(mode) => serde_json::from_value(mode.clone())
+                        .map_err(|e| SageXError::configuration(format!("Modo HTTP inválido: {}", e)))?,
+                    None => HttpMode::default(),
                 };
                 
-                Ok(Box::new(HttpTransport::new(base_url)))
+                Ok(Box::new(HttpTransport::new(base_url).with_mode(mode)))
             }
             
             TransportType::Stdio => {
                 Ok(Box::new(StdioTransport::new()))
             }
             
+            TransportType::Process => {
+                let config = config
+                    .ok_or_else(|| SageXError::configuration("Transporte de processo requer 'command'"))?;
+                let process_config: ProcessConfig = serde_json::from_value(config)
+                    .map_err(|e| SageXError::configuration(format!("Configuração de processo inválida: {}", e)))?;
+                
+                Ok(Box::new(ProcessTransport::new(process_config)))
+            }
+            
             TransportType::WebSocket => {
-                // TODO: Implementar WebSocket transport
-                Err(SageXError::configuration("WebSocket transport não implementado ainda"))
+                let websocket_config = match config {
+                    Some(config) => serde_json::from_value(config)
+                        .map_err(|e| SageXError::configuration(format!("Configuração WebSocket inválida: {}", e)))?,
+                    None => WebSocketConfig::default(),
+                };
+                
+                Ok(Box::new(WebSocketTransport::new(websocket_config)))
             }
             
             TransportType::Mock => {
@@ -391,7 +1575,8 @@ impl TransportFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mcp::messages::McpRequest;
+    use crate::mcp::messages::{McpRequest, RequestId};
+    use tokio_tungstenite::tungstenite::handshake::server::{Callback, ErrorResponse, Request, Response};
     
     #[tokio::test]
     async fn test_mock_transport() {
@@ -422,9 +1607,20 @@ mod tests {
         assert!(received.is_some());
         assert!(received.unwrap().is_response());
         
+        // Quem aguarda é acordado pela próxima mensagem
+        let waiting = {
+            let transport = transport.clone();
+            tokio::spawn(async move { transport.next_message().await })
+        };
+        tokio::task::yield_now().await;
+        transport.add_incoming_message(McpMessage::new_notification("x".to_string(), None)).await;
+        let received = tokio::time::timeout(std::time::Duration::from_secs(1), waiting).await.unwrap().unwrap().unwrap();
+        assert!(received.unwrap().is_notification());
+        
         // Fechar
         transport.close().await.unwrap();
         assert!(!transport.is_connected().await);
+        assert!(transport.next_message().await.unwrap().is_none());
     }
     
     #[tokio::test]
@@ -444,9 +1640,138 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[tokio::test]
+    async fn test_http_transport_event_stream() {
+        let mut server = mockito::Server::new_async().await;
+        let _health = server.mock("GET", "/health").with_status(200).create_async().await;
+        
+        let first = McpMessage::new_notification("notifications/first".to_string(), None);
+        let second = McpMessage::new_success_response("req-2".to_string(), serde_json::json!({}));
+        let body = format!(
+            "retry: 10\nid: 1\ndata: {}\n\n: comentário\n\nevent: ping\ndata: ignorado\n\nid: 2\ndata: {}\n\n",
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap(),
+        );
+        let initial = server.mock("GET", "/mcp/events")
+            .match_header("accept", "text/event-stream")
+            .match_header("last-event-id", mockito::Matcher::Missing)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+        let resumed = server.mock("GET", "/mcp/events")
+            .match_header("last-event-id", "2")
+            .with_header("content-type", "text/event-stream")
+            .with_body("")
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        
+        let mut transport = HttpTransport::new(server.url());
+        transport.initialize().await.unwrap();
+        
+        let received = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(received.method(), Some("notifications/first"));
+        let received = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(received.id(), Some(&"req-2".into()));
+        assert_eq!(transport.last_event_id().await.as_deref(), Some("2"));
+        
+        // Ao reconectar, o stream retoma a partir do último evento
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !resumed.matched_async().await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        initial.assert_async().await;
+        
+        transport.close().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_streamable_http_session_lifecycle() {
+        let mut server = mockito::Server::new_async().await;
+        let response = McpMessage::new_success_response("init-1".to_string(), serde_json::json!({}));
+        
+        let initialize = server.mock("POST", "/mcp")
+            .match_header("accept", "application/json, text/event-stream")
+            .match_header("mcp-session-id", mockito::Matcher::Missing)
+            .with_header("content-type", "application/json")
+            .with_header("mcp-session-id", "session-abc")
+            .with_body(serde_json::to_string(&response).unwrap())
+            .create_async()
+            .await;
+        let notification = server.mock("POST", "/mcp")
+            .match_header("mcp-session-id", "session-abc")
+            .with_status(202)
+            .create_async()
+            .await;
+        let _get_stream = server.mock("GET", "/mcp").with_status(405).create_async().await;
+        let delete = server.mock("DELETE", "/mcp")
+            .match_header("mcp-session-id", "session-abc")
+            .expect(1)
+            .create_async()
+            .await;
+        
+        let mut transport = HttpTransport::streamable(format!("{}/mcp", server.url()));
+        assert_eq!(transport.mode(), HttpMode::Streamable);
+        transport.initialize().await.unwrap();
+        
+        let request = McpMessage::Request(McpRequest::ping("init-1".to_string()));
+        transport.send_message(request).await.unwrap();
+        assert_eq!(transport.session_id().await.as_deref(), Some("session-abc"));
+        
+        let received = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(received.id(), Some(&"init-1".into()));
+        
+        let initialized = McpMessage::new_notification("notifications/initialized".to_string(), None);
+        transport.send_message(initialized).await.unwrap();
+        
+        transport.close().await.unwrap();
+        initialize.assert_async().await;
+        notification.assert_async().await;
+        delete.assert_async().await;
+        assert!(transport.session_id().await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_streamable_http_event_stream_response() {
+        let mut server = mockito::Server::new_async().await;
+        let progress = McpMessage::new_notification("notifications/progress".to_string(), None);
+        let result = McpMessage::new_success_response("call-1".to_string(), serde_json::json!({}));
+        let body = format!(
+            "id: 7\ndata: {}\n\ndata: {}\n\n",
+            serde_json::to_string(&progress).unwrap(),
+            serde_json::to_string(&result).unwrap(),
+        );
+        let _post = server.mock("POST", "/mcp")
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+        let _get_stream = server.mock("GET", "/mcp").with_status(405).create_async().await;
+        
+        let mut transport = HttpTransport::streamable(format!("{}/mcp", server.url()));
+        transport.initialize().await.unwrap();
+        
+        let request = McpMessage::Request(McpRequest::ping("call-1".to_string()));
+        transport.send_message(request).await.unwrap();
+        
+        let first = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(first.method(), Some("notifications/progress"));
+        let second = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(second.id(), Some(&"call-1".into()));
+        // IDs do stream de resposta não são usados para retomar o stream GET
+        assert_eq!(transport.last_event_id().await, None);
+        
+        transport.close().await.unwrap();
+    }
+    
     #[tokio::test]
     async fn test_stdio_transport() {
-        let mut transport = StdioTransport::new();
+        let (_client_side, server_side) = tokio::io::duplex(1024);
+        let (reader, writer) = tokio::io::split(server_side);
+        let mut transport = StdioTransport::with_streams(Box::new(reader), Box::new(writer));
         
         // Inicializar
         transport.initialize().await.unwrap();
@@ -458,6 +1783,53 @@ mod tests {
         assert!(!transport.is_connected().await);
     }
     
+    #[tokio::test]
+    async fn test_stdio_transport_line_framing() {
+        let (client_side, server_side) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(server_side);
+        let mut transport = StdioTransport::with_streams(Box::new(reader), Box::new(writer));
+        transport.initialize().await.unwrap();
+        
+        let (client_reader, mut client_writer) = tokio::io::split(client_side);
+        
+        // Mensagem enviada sai como uma linha JSON
+        let request = McpMessage::Request(McpRequest::ping("out-1".to_string()));
+        transport.send_message(request).await.unwrap();
+        let mut lines = BufReader::new(client_reader).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        let sent: McpMessage = serde_json::from_str(&line).unwrap();
+        assert_eq!(sent.id(), Some(&"out-1".into()));
+        
+        // Linhas recebidas viram mensagens; linhas inválidas são ignoradas
+        let response = McpMessage::new_success_response("in-1".to_string(), serde_json::json!({}));
+        let payload = format!("not json\n\n{}\n", serde_json::to_string(&response).unwrap());
+        client_writer.write_all(payload.as_bytes()).await.unwrap();
+        
+        let received = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(received.id(), Some(&"in-1".into()));
+        
+        // Lotes JSON-RPC são desfeitos em mensagens individuais
+        client_writer.write_all(b"[{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}},{\"jsonrpc\":\"2.0\",\"method\":\"x\"}]\n").await.unwrap();
+        let first = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(first.id(), Some(&RequestId::Number(1)));
+        let second = next_event(&transport).await.unwrap().unwrap();
+        assert!(second.is_notification());
+        
+        transport.send_batch(vec![
+            McpMessage::Request(McpRequest::ping(2)),
+            McpMessage::Request(McpRequest::ping(3)),
+        ]).await.unwrap();
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert!(line.starts_with('['));
+        
+        // EOF é reportado como desconexão a quem aguarda a próxima mensagem
+        drop(client_writer);
+        drop(lines);
+        let next = tokio::time::timeout(std::time::Duration::from_secs(1), transport.next_message()).await.unwrap();
+        assert!(next.unwrap().is_none());
+        assert!(!transport.is_connected().await);
+    }
+    
     #[test]
     fn test_transport_factory() {
         let http_transport = TransportFactory::create(
@@ -466,14 +1838,212 @@ mod tests {
         ).unwrap();
         assert_eq!(http_transport.transport_type(), TransportType::Http);
         
+        let legacy_transport = TransportFactory::create(
+            TransportType::Http,
+            Some(serde_json::json!({"base_url": "http://test.com", "mode": "legacy"}))
+        ).unwrap();
+        assert_eq!(legacy_transport.transport_type(), TransportType::Http);
+        
+        let invalid_mode = TransportFactory::create(
+            TransportType::Http,
+            Some(serde_json::json!({"mode": "carrier-pigeon"}))
+        );
+        assert!(invalid_mode.is_err());
+        
         let stdio_transport = TransportFactory::create(TransportType::Stdio, None).unwrap();
         assert_eq!(stdio_transport.transport_type(), TransportType::Stdio);
         
         let mock_transport = TransportFactory::create(TransportType::Mock, None).unwrap();
         assert_eq!(mock_transport.transport_type(), TransportType::Mock);
         
-        let websocket_result = TransportFactory::create(TransportType::WebSocket, None);
-        assert!(websocket_result.is_err());
+        let process_transport = TransportFactory::create(
+            TransportType::Process,
+            Some(serde_json::json!({"command": "npx", "args": ["some-server"]}))
+        ).unwrap();
+        assert_eq!(process_transport.transport_type(), TransportType::Process);
+        
+        let process_result = TransportFactory::create(TransportType::Process, None);
+        assert!(process_result.is_err());
+        
+        let websocket_transport = TransportFactory::create(
+            TransportType::WebSocket,
+            Some(serde_json::json!({"url": "ws://test.com/mcp", "ping_interval_ms": 15000, "connect_timeout_ms": 5000}))
+        ).unwrap();
+        assert_eq!(websocket_transport.transport_type(), TransportType::WebSocket);
+    }
+    
+    #[test]
+    fn test_websocket_config_durations_in_millis() {
+        let config: WebSocketConfig = serde_json::from_value(serde_json::json!({
+            "url": "ws://test.com/mcp",
+            "ping_interval_ms": 15000,
+            "connect_timeout_ms": 2500,
+        })).unwrap();
+        assert_eq!(config.ping_interval, Some(Duration::from_secs(15)));
+        assert_eq!(config.connect_timeout, Duration::from_millis(2500));
+        
+        let value = serde_json::to_value(config.with_ping_interval(None)).unwrap();
+        assert_eq!(value["ping_interval_ms"], serde_json::Value::Null);
+        assert_eq!(value["connect_timeout_ms"], 2500);
+        
+        // Campos ausentes mantêm os padrões
+        let config: WebSocketConfig = serde_json::from_value(serde_json::json!({"url": "ws://x"})).unwrap();
+        assert_eq!(config.ping_interval, WebSocketConfig::default().ping_interval);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_process_transport_roundtrip() {
+        // `cat` devolve cada linha enviada, servindo como servidor de eco
+        let mut transport = ProcessTransport::new(ProcessConfig::new("cat"));
+        transport.initialize().await.unwrap();
+        assert!(transport.is_connected().await);
+        assert!(transport.pid().is_some());
+        
+        let request = McpMessage::Request(McpRequest::ping("echo-1".to_string()));
+        transport.send_message(request).await.unwrap();
+        
+        let echoed = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(message) = transport.receive_message().await.unwrap() {
+                    return message;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        }).await.unwrap();
+        assert_eq!(echoed.id(), Some(&"echo-1".into()));
+        
+        transport.close().await.unwrap();
+        assert!(!transport.is_connected().await);
+        assert!(transport.pid().is_none());
+    }
+    
+    /// Callback do handshake que registra os headers e ecoa o subprotocolo pedido
+    struct RecordHeaders(tokio::sync::oneshot::Sender<HashMap<String, String>>);
+    
+    impl Callback for RecordHeaders {
+        fn on_request(self, request: &Request, mut response: Response) -> Result<Response, ErrorResponse> {
+            let headers: HashMap<String, String> = request.headers().iter()
+                .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or_default().to_string()))
+                .collect();
+            if let Some(protocol) = request.headers().get("sec-websocket-protocol") {
+                response.headers_mut().insert("sec-websocket-protocol", protocol.clone());
+            }
+            let _ = self.0.send(headers);
+            Ok(response)
+        }
+    }
+    
+    /// Inicia um servidor WebSocket local que atende uma única conexão
+    async fn spawn_websocket_server<F, Fut>(handler: F) -> (String, tokio::sync::oneshot::Receiver<HashMap<String, String>>)
+    where
+        F: FnOnce(WebSocketStream<TcpStream>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (headers_sender, headers_receiver) = tokio::sync::oneshot::channel();
+        
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let stream = tokio_tungstenite::accept_hdr_async(socket, RecordHeaders(headers_sender)).await.unwrap();
+            handler(stream).await;
+        });
+        
+        (url, headers_receiver)
+    }
+    
+    /// Aguarda a próxima mensagem ou erro do transporte
+    async fn next_event(transport: &dyn Transport) -> SageXResult<Option<McpMessage>> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match transport.receive_message().await {
+                    Ok(None) if transport.is_connected().await => {
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                    }
+                    other => return other,
+                }
+            }
+        }).await.unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_websocket_transport_roundtrip() {
+        let (url, headers) = spawn_websocket_server(|mut stream| async move {
+            // Ecoa o primeiro frame de texto e fecha normalmente
+            while let Some(Ok(frame)) = stream.next().await {
+                if frame.is_text() {
+                    stream.send(frame).await.unwrap();
+                    break;
+                }
+            }
+            stream.close(None).await.unwrap();
+        }).await;
+        
+        let config = WebSocketConfig::new(url)
+            .with_bearer_token("secret")
+            .with_header("X-Client", "sage-x");
+        let mut transport = WebSocketTransport::new(config);
+        transport.initialize().await.unwrap();
+        assert!(transport.is_connected().await);
+        
+        let headers = headers.await.unwrap();
+        assert_eq!(headers.get("authorization").map(String::as_str), Some("Bearer secret"));
+        assert_eq!(headers.get("sec-websocket-protocol").map(String::as_str), Some("mcp"));
+        assert_eq!(headers.get("x-client").map(String::as_str), Some("sage-x"));
+        
+        let request = McpMessage::Request(McpRequest::ping("ws-1".to_string()));
+        transport.send_message(request).await.unwrap();
+        
+        let echoed = next_event(&transport).await.unwrap().unwrap();
+        assert_eq!(echoed.id(), Some(&"ws-1".into()));
+        
+        // Fechamento normal vira desconexão sem erro
+        assert!(next_event(&transport).await.unwrap().is_none());
+        assert!(!transport.is_connected().await);
+        
+        transport.close().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_websocket_transport_abnormal_close() {
+        let (url, _headers) = spawn_websocket_server(|mut stream| async move {
+            let frame = CloseFrame {
+                code: CloseCode::Error,
+                reason: "falha interna".into(),
+            };
+            stream.close(Some(frame)).await.unwrap();
+        }).await;
+        
+        let mut transport = WebSocketTransport::new(WebSocketConfig::new(url).with_ping_interval(None));
+        transport.initialize().await.unwrap();
+        
+        let result = next_event(&transport).await;
+        assert!(matches!(result, Err(SageXError::Connection { .. })));
+        assert!(!transport.is_connected().await);
+    }
+    
+    #[test]
+    fn test_websocket_config_from_sage_config() {
+        let mut sage_config = SageXConfig {
+            auth_token: "token".to_string(),
+            ..Default::default()
+        };
+        sage_config.network.custom_headers.insert("X-Team".to_string(), "core".to_string());
+        
+        let config = WebSocketConfig::from_sage_config("wss://api.sage-x.ai/mcp", &sage_config);
+        assert_eq!(config.bearer_token.as_deref(), Some("token"));
+        assert_eq!(config.headers.get("X-Team").map(String::as_str), Some("core"));
+        
+        let request = config.build_request().unwrap();
+        assert_eq!(request.headers().get("authorization").unwrap(), "Bearer token");
+    }
+    
+    #[tokio::test]
+    async fn test_process_transport_spawn_failure() {
+        let mut transport = ProcessTransport::new(ProcessConfig::new("sage-x-command-that-does-not-exist"));
+        assert!(transport.initialize().await.is_err());
+        assert!(!transport.is_connected().await);
     }
 }
 
@@ -0,0 +1,717 @@
+//! Tipos de conteúdo do protocolo MCP
+//!
+//! Estruturas tipadas para os resultados trocados com servidores MCP.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SageXError, SageXResult};
+use crate::models::{McpResource, McpTool};
+use super::messages::ProgressToken;
+use super::uri_template::UriTemplate;
+
+/// Bloco de conteúdo MCP
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum McpContent {
+    /// Texto
+    Text {
+        /// Conteúdo textual
+        text: String,
+    },
+
+    /// Imagem codificada em base64
+    Image {
+        /// Dados em base64
+        data: String,
+        /// Tipo MIME da imagem
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+
+    /// Áudio codificado em base64
+    Audio {
+        /// Dados em base64
+        data: String,
+        /// Tipo MIME do áudio
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+
+    /// Resource embutido
+    Resource {
+        /// Conteúdo do resource
+        resource: ResourceContents,
+    },
+
+    /// Link para um resource do servidor
+    ResourceLink {
+        /// URI do resource
+        uri: String,
+        /// Nome do resource
+        name: String,
+        /// Descrição do resource
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Tipo MIME
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+    },
+
+    /// Tipo de conteúdo desconhecido (versões futuras do protocolo)
+    #[serde(other)]
+    Unknown,
+}
+
+impl McpContent {
+    /// Cria um bloco de texto
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Obtém o texto, se for um bloco textual
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text { text } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Conteúdo de um resource (texto ou binário)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResourceContents {
+    /// Conteúdo textual
+    Text {
+        /// URI do resource
+        uri: String,
+        /// Tipo MIME
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// Texto do resource
+        text: String,
+    },
+
+    /// Conteúdo binário em base64
+    Blob {
+        /// URI do resource
+        uri: String,
+        /// Tipo MIME
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// Dados em base64
+        blob: String,
+    },
+}
+
+impl ResourceContents {
+    /// Obtém a URI do resource
+    pub fn uri(&self) -> &str {
+        match self {
+            Self::Text { uri, .. } | Self::Blob { uri, .. } => uri,
+        }
+    }
+
+    /// Obtém o tipo MIME do resource
+    pub fn mime_type(&self) -> Option<&str> {
+        match self {
+            Self::Text { mime_type, .. } | Self::Blob { mime_type, .. } => mime_type.as_deref(),
+        }
+    }
+
+    /// Obtém o texto, se o conteúdo for textual
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text { text, .. } => Some(text),
+            Self::Blob { .. } => None,
+        }
+    }
+
+    /// Obtém os bytes do conteúdo, decodificando o base64 de blobs
+    pub fn to_bytes(&self) -> SageXResult<Vec<u8>> {
+        use base64::Engine;
+
+        match self {
+            Self::Text { text, .. } => Ok(text.clone().into_bytes()),
+            Self::Blob { blob, .. } => base64::engine::general_purpose::STANDARD
+                .decode(blob)
+                .map_err(|e| SageXError::serialization(format!("Blob base64 inválido em {}: {}", self.uri(), e))),
+        }
+    }
+}
+
+/// Resultado de `tools/call`
+///
+/// Falhas da própria ferramenta chegam com `is_error = true`; erros de
+/// protocolo são reportados como `SageXError::McpRemote`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallResult {
+    /// Conteúdo retornado pela ferramenta
+    #[serde(default)]
+    pub content: Vec<McpContent>,
+
+    /// Resultado estruturado, se a ferramenta declarar `outputSchema`
+    #[serde(rename = "structuredContent", default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
+
+    /// Indica que a ferramenta falhou
+    #[serde(rename = "isError", default)]
+    pub is_error: bool,
+}
+
+impl ToolCallResult {
+    /// Concatena os blocos de texto do resultado
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(McpContent::as_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Identificação de um cliente ou servidor MCP (`clientInfo`/`serverInfo`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Implementation {
+    /// Nome da implementação
+    pub name: String,
+
+    /// Versão da implementação
+    pub version: String,
+
+    /// Nome para exibição
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Implementation {
+    /// Cria uma identificação
+    pub fn new<S1: Into<String>, S2: Into<String>>(name: S1, version: S2) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            title: None,
+        }
+    }
+}
+
+/// Capacidade que apenas indica suporte a `listChanged`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListChangedCapability {
+    /// Emite notificações `*/list_changed`
+    #[serde(rename = "listChanged", default)]
+    pub list_changed: bool,
+}
+
+/// Capacidade de resources do servidor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourcesCapability {
+    /// Aceita `resources/subscribe`
+    #[serde(default)]
+    pub subscribe: bool,
+
+    /// Emite `notifications/resources/list_changed`
+    #[serde(rename = "listChanged", default)]
+    pub list_changed: bool,
+}
+
+/// Capacidades anunciadas pelo cliente no `initialize`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientCapabilities {
+    /// Suporte a `roots/list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<ListChangedCapability>,
+
+    /// Suporte a `sampling/createMessage`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<serde_json::Value>,
+
+    /// Suporte a `elicitation/create`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<serde_json::Value>,
+
+    /// Capacidades não padronizadas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Capacidades anunciadas pelo servidor no `initialize`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// Oferece ferramentas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<ListChangedCapability>,
+
+    /// Oferece resources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+
+    /// Oferece prompts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<ListChangedCapability>,
+
+    /// Envia mensagens de log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<serde_json::Value>,
+
+    /// Responde a `completion/complete`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<serde_json::Value>,
+
+    /// Capacidades não padronizadas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Parâmetros do request `initialize`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitializeParams {
+    /// Versão do protocolo solicitada
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: String,
+
+    /// Capacidades do cliente
+    pub capabilities: ClientCapabilities,
+
+    /// Identificação do cliente
+    #[serde(rename = "clientInfo")]
+    pub client_info: Implementation,
+}
+
+/// Resultado do request `initialize`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitializeResult {
+    /// Versão do protocolo escolhida pelo servidor
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: String,
+
+    /// Capacidades do servidor
+    #[serde(default)]
+    pub capabilities: ServerCapabilities,
+
+    /// Identificação do servidor
+    #[serde(rename = "serverInfo", default)]
+    pub server_info: Implementation,
+
+    /// Instruções de uso fornecidas pelo servidor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// Parâmetros de `notifications/progress`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressUpdate {
+    /// Token informado no request em `_meta.progressToken`
+    #[serde(rename = "progressToken")]
+    pub progress_token: ProgressToken,
+
+    /// Progresso atual (cresce a cada notificação)
+    pub progress: f64,
+
+    /// Total esperado, se conhecido
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+
+    /// Descrição do passo atual
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ProgressUpdate {
+    /// Fração concluída (0.0 a 1.0), se o total for conhecido
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0.0)
+            .map(|total| (self.progress / total).clamp(0.0, 1.0))
+    }
+}
+
+/// Resultado paginado de uma operação `*/list`
+pub trait Paginated {
+    /// Tipo dos itens da página
+    type Item;
+
+    /// Separa os itens da página e o cursor da próxima
+    fn into_page(self) -> (Vec<Self::Item>, Option<String>);
+}
+
+/// Resultado de `tools/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListToolsResult {
+    /// Ferramentas desta página
+    #[serde(default)]
+    pub tools: Vec<McpTool>,
+
+    /// Cursor da próxima página
+    #[serde(rename = "nextCursor", default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl Paginated for ListToolsResult {
+    type Item = McpTool;
+
+    fn into_page(self) -> (Vec<McpTool>, Option<String>) {
+        (self.tools, self.next_cursor)
+    }
+}
+
+/// Resultado de `resources/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourcesResult {
+    /// Resources desta página
+    #[serde(default)]
+    pub resources: Vec<McpResource>,
+
+    /// Cursor da próxima página
+    #[serde(rename = "nextCursor", default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl Paginated for ListResourcesResult {
+    type Item = McpResource;
+
+    fn into_page(self) -> (Vec<McpResource>, Option<String>) {
+        (self.resources, self.next_cursor)
+    }
+}
+
+/// Resultado de `resources/read`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    /// Conteúdos do resource (um resource pode ter várias partes)
+    #[serde(default)]
+    pub contents: Vec<ResourceContents>,
+}
+
+/// Template de resource parametrizado por URI template (RFC 6570)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    /// URI template
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+
+    /// Nome do template
+    pub name: String,
+
+    /// Nome de exibição
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Descrição do template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Tipo MIME dos resources gerados
+    #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl ResourceTemplate {
+    /// Expande o template com as variáveis informadas
+    pub fn expand(&self, variables: &HashMap<String, String>) -> SageXResult<String> {
+        Ok(UriTemplate::parse(self.uri_template.as_str())?.expand(variables))
+    }
+}
+
+/// Resultado de `resources/templates/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    /// Templates desta página
+    #[serde(rename = "resourceTemplates", default)]
+    pub resource_templates: Vec<ResourceTemplate>,
+
+    /// Cursor da próxima página
+    #[serde(rename = "nextCursor", default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl Paginated for ListResourceTemplatesResult {
+    type Item = ResourceTemplate;
+
+    fn into_page(self) -> (Vec<ResourceTemplate>, Option<String>) {
+        (self.resource_templates, self.next_cursor)
+    }
+}
+
+/// Parâmetros de `notifications/resources/updated`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUpdated {
+    /// URI do resource alterado
+    pub uri: String,
+
+    /// Nome de exibição
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Alvo de uma completação de argumento
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// Argumento de um prompt
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// Nome do prompt
+        name: String,
+    },
+
+    /// Variável de um resource template
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// URI template do resource
+        uri: String,
+    },
+}
+
+impl CompletionReference {
+    /// Referência a um prompt
+    pub fn prompt<S: Into<String>>(name: S) -> Self {
+        Self::Prompt { name: name.into() }
+    }
+
+    /// Referência a um resource template
+    pub fn resource<S: Into<String>>(uri_template: S) -> Self {
+        Self::Resource { uri: uri_template.into() }
+    }
+}
+
+/// Argumento sendo completado
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    /// Nome do argumento ou variável
+    pub name: String,
+
+    /// Valor parcial digitado
+    pub value: String,
+}
+
+/// Argumentos já preenchidos, usados pelo servidor para refinar sugestões
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompletionContext {
+    /// Valores já informados, por nome
+    #[serde(default)]
+    pub arguments: BTreeMap<String, String>,
+}
+
+/// Parâmetros de `completion/complete`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompleteParams {
+    /// Prompt ou resource template
+    #[serde(rename = "ref")]
+    pub reference: CompletionReference,
+
+    /// Argumento sendo completado
+    pub argument: CompletionArgument,
+
+    /// Argumentos já preenchidos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CompletionContext>,
+}
+
+impl CompleteParams {
+    /// Cria os parâmetros para o valor parcial de um argumento
+    pub fn new<N: Into<String>, V: Into<String>>(reference: CompletionReference, name: N, value: V) -> Self {
+        Self {
+            reference,
+            argument: CompletionArgument {
+                name: name.into(),
+                value: value.into(),
+            },
+            context: None,
+        }
+    }
+
+    /// Informa os argumentos já preenchidos
+    pub fn with_context(mut self, arguments: BTreeMap<String, String>) -> Self {
+        self.context = Some(CompletionContext { arguments });
+        self
+    }
+}
+
+/// Sugestões retornadas pelo servidor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    /// Valores sugeridos (no máximo 100)
+    #[serde(default)]
+    pub values: Vec<String>,
+
+    /// Total de sugestões disponíveis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+
+    /// Há mais sugestões além das retornadas
+    #[serde(rename = "hasMore", default)]
+    pub has_more: bool,
+}
+
+/// Resultado de `completion/complete`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompleteResult {
+    /// Sugestões
+    pub completion: Completion,
+}
+
+/// Papel do autor de uma mensagem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Usuário
+    User,
+    /// Assistente (modelo)
+    Assistant,
+}
+
+/// Argumento declarado por um prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Nome do argumento
+    pub name: String,
+
+    /// Descrição do argumento
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Se o argumento é obrigatório
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Template de prompt oferecido pelo servidor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpPrompt {
+    /// Nome único do prompt
+    pub name: String,
+
+    /// Nome de exibição
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Descrição do prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Argumentos aceitos
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+}
+
+impl McpPrompt {
+    /// Valida os argumentos contra os declarados pelo prompt
+    ///
+    /// Falha se faltar um argumento obrigatório ou se houver argumento não declarado.
+    pub fn validate_arguments(&self, arguments: &HashMap<String, String>) -> SageXResult<()> {
+        if let Some(missing) = self
+            .arguments
+            .iter()
+            .find(|argument| argument.required && !arguments.contains_key(&argument.name))
+        {
+            return Err(SageXError::validation(
+                missing.name.clone(),
+                format!("Argumento obrigatório ausente no prompt {}", self.name),
+            ));
+        }
+
+        let mut unknown: Vec<&str> = arguments
+            .keys()
+            .filter(|key| !self.arguments.iter().any(|argument| &argument.name == *key))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort();
+            return Err(SageXError::validation(
+                unknown.join(", "),
+                format!("Argumento não declarado pelo prompt {}", self.name),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Resultado de `prompts/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    /// Prompts desta página
+    #[serde(default)]
+    pub prompts: Vec<McpPrompt>,
+
+    /// Cursor da próxima página
+    #[serde(rename = "nextCursor", default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl Paginated for ListPromptsResult {
+    type Item = McpPrompt;
+
+    fn into_page(self) -> (Vec<McpPrompt>, Option<String>) {
+        (self.prompts, self.next_cursor)
+    }
+}
+
+/// Mensagem de um prompt renderizado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// Autor da mensagem
+    pub role: Role,
+
+    /// Conteúdo da mensagem
+    pub content: McpContent,
+}
+
+/// Resultado de `prompts/get`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    /// Descrição do prompt renderizado
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Mensagens geradas pelo template
+    pub messages: Vec<PromptMessage>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_call_result_parsing() {
+        let result: ToolCallResult = serde_json::from_value(serde_json::json!({
+            "content": [
+                {"type": "text", "text": "linha 1"},
+                {"type": "image", "data": "aGVsbG8=", "mimeType": "image/png"},
+                {"type": "resource", "resource": {"uri": "file:///a.bin", "blob": "AAE="}},
+                {"type": "resource_link", "uri": "file:///b.rs", "name": "b.rs"},
+                {"type": "hologram"},
+                {"type": "text", "text": "linha 2"}
+            ],
+            "structuredContent": {"ok": true}
+        })).unwrap();
+
+        assert!(!result.is_error);
+        assert_eq!(result.text(), "linha 1\nlinha 2");
+        assert!(matches!(&result.content[2], McpContent::Resource { resource: ResourceContents::Blob { .. } }));
+        assert_eq!(result.content[4], McpContent::Unknown);
+        assert_eq!(result.structured_content, Some(serde_json::json!({"ok": true})));
+    }
+
+    #[test]
+    fn test_initialize_fixtures() {
+        let request: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/jsonrpc/initialize_request.json")).unwrap();
+        let params: InitializeParams = serde_json::from_value(request["params"].clone()).unwrap();
+        assert_eq!(params.protocol_version, "2025-06-18");
+        assert_eq!(params.capabilities.roots, Some(ListChangedCapability { list_changed: true }));
+        assert!(params.capabilities.sampling.is_some());
+        assert_eq!(serde_json::to_value(&params).unwrap(), request["params"]);
+
+        let response: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/jsonrpc/initialize_response.json")).unwrap();
+        let result: InitializeResult = serde_json::from_value(response["result"].clone()).unwrap();
+        assert_eq!(result.server_info.name, "example-server");
+        assert!(result.capabilities.resources.as_ref().is_some_and(|r| r.subscribe));
+        assert!(result.capabilities.completions.is_some());
+        assert!(result.instructions.is_some());
+    }
+}
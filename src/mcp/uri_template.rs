@@ -0,0 +1,280 @@
+//! Expansão de URI templates (RFC 6570)
+//!
+//! Usado pelos resource templates (`resources/templates/list`). Suporta todos os
+//! operadores do nível 4 e o modificador de prefixo (`{var:3}`) para valores
+//! textuais; o modificador `*` é aceito, mas não altera valores simples.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::{SageXError, SageXResult};
+
+/// Caracteres reservados mantidos pelos operadores `+` e `#`
+const RESERVED: &str = ":/?#[]@!$&'()*+,;=";
+
+/// Parte de um template
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    /// Texto literal
+    Literal(String),
+    /// Expressão entre chaves
+    Expression(Expression),
+}
+
+/// Expressão `{op var1,var2:3}`
+#[derive(Debug, Clone, PartialEq)]
+struct Expression {
+    operator: Option<char>,
+    variables: Vec<VarSpec>,
+}
+
+/// Variável de uma expressão
+#[derive(Debug, Clone, PartialEq)]
+struct VarSpec {
+    name: String,
+    prefix: Option<usize>,
+}
+
+/// Regras de expansão de cada operador
+struct OperatorRules {
+    first: &'static str,
+    separator: &'static str,
+    named: bool,
+    if_empty: &'static str,
+    allow_reserved: bool,
+}
+
+impl OperatorRules {
+    fn of(operator: Option<char>) -> Self {
+        let (first, separator, named, if_empty, allow_reserved) = match operator {
+            None => ("", ",", false, "", false),
+            Some('+') => ("", ",", false, "", true),
+            Some('.') => (".", ".", false, "", false),
+            Some('/') => ("/", "/", false, "", false),
+            Some(';') => (";", ";", true, "", false),
+            Some('?') => ("?", "&", true, "=", false),
+            Some('&') => ("&", "&", true, "=", false),
+            Some('#') => ("#", ",", false, "", true),
+            Some(_) => unreachable!("operador validado no parse"),
+        };
+        Self { first, separator, named, if_empty, allow_reserved }
+    }
+}
+
+/// URI template já analisado
+#[derive(Debug, Clone, PartialEq)]
+pub struct UriTemplate {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl UriTemplate {
+    /// Analisa um template
+    pub fn parse<S: Into<String>>(template: S) -> SageXResult<Self> {
+        let source = template.into();
+        let mut parts = Vec::new();
+        let mut rest = source.as_str();
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| invalid(&source, "chave não fechada"))?;
+            parts.push(Part::Expression(parse_expression(&source, &rest[start + 1..start + end])?));
+            rest = &rest[start + end + 1..];
+        }
+        if rest.contains('}') {
+            return Err(invalid(&source, "chave fechada sem abertura"));
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+
+        Ok(Self { source, parts })
+    }
+
+    /// Template original
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Nomes das variáveis, na ordem em que aparecem
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            if let Part::Expression(expression) = part {
+                for variable in &expression.variables {
+                    if !names.contains(&variable.name.as_str()) {
+                        names.push(&variable.name);
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// Expande o template; variáveis ausentes são omitidas
+    pub fn expand(&self, variables: &HashMap<String, String>) -> String {
+        let mut uri = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => uri.push_str(literal),
+                Part::Expression(expression) => expand_expression(&mut uri, expression, variables),
+            }
+        }
+        uri
+    }
+}
+
+impl fmt::Display for UriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn invalid(template: &str, reason: &str) -> SageXError {
+    SageXError::validation("uri_template", format!("Template inválido ({}): {}", reason, template))
+}
+
+fn parse_expression(template: &str, body: &str) -> SageXResult<Expression> {
+    let operator = body.chars().next().filter(|c| "+#./;?&".contains(*c));
+    let list = match operator {
+        Some(op) => &body[op.len_utf8()..],
+        None => body,
+    };
+
+    let variables = list
+        .split(',')
+        .map(|spec| {
+            let spec = spec.strip_suffix('*').unwrap_or(spec);
+            let (name, prefix) = match spec.split_once(':') {
+                Some((name, length)) => {
+                    let length = length
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|length| (1..10_000).contains(length))
+                        .ok_or_else(|| invalid(template, "prefixo inválido"))?;
+                    (name, Some(length))
+                }
+                None => (spec, None),
+            };
+            let valid = !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '%');
+            if !valid {
+                return Err(invalid(template, "nome de variável inválido"));
+            }
+            Ok(VarSpec { name: name.to_string(), prefix })
+        })
+        .collect::<SageXResult<Vec<_>>>()?;
+
+    Ok(Expression { operator, variables })
+}
+
+fn expand_expression(uri: &mut String, expression: &Expression, variables: &HashMap<String, String>) {
+    let rules = OperatorRules::of(expression.operator);
+    let mut first = true;
+
+    for spec in &expression.variables {
+        let Some(value) = variables.get(&spec.name) else {
+            continue;
+        };
+
+        uri.push_str(if first { rules.first } else { rules.separator });
+        first = false;
+
+        if rules.named {
+            uri.push_str(&spec.name);
+            if value.is_empty() {
+                uri.push_str(rules.if_empty);
+                continue;
+            }
+            uri.push('=');
+        }
+
+        let value = match spec.prefix {
+            Some(length) => value.chars().take(length).collect::<String>(),
+            None => value.clone(),
+        };
+        encode(uri, &value, rules.allow_reserved);
+    }
+}
+
+/// Codifica o valor, mantendo reservados e triplas `%XX` quando permitido
+fn encode(uri: &mut String, value: &str, allow_reserved: bool) {
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let c = byte as char;
+        let is_triplet = allow_reserved
+            && byte == b'%'
+            && bytes.get(index + 1..index + 3).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+
+        if byte.is_ascii_alphanumeric() || "-._~".contains(c) || (allow_reserved && RESERVED.contains(c)) || is_triplet {
+            uri.push(c);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Variáveis dos exemplos da RFC 6570, seção 3.2
+    fn rfc_variables() -> HashMap<String, String> {
+        [
+            ("var", "value"),
+            ("hello", "Hello World!"),
+            ("path", "/foo/bar"),
+            ("empty", ""),
+            ("x", "1024"),
+            ("y", "768"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_rfc_examples() {
+        let variables = rfc_variables();
+        let cases = [
+            ("{var}", "value"),
+            ("{hello}", "Hello%20World%21"),
+            ("{+hello}", "Hello%20World!"),
+            ("{+path}/here", "/foo/bar/here"),
+            ("{#path,x}/here", "#/foo/bar,1024/here"),
+            ("map?{x,y}", "map?1024,768"),
+            ("{x,hello,y}", "1024,Hello%20World%21,768"),
+            ("X{.var}", "X.value"),
+            ("{/var,x}/here", "/value/1024/here"),
+            ("{;x,y,empty}", ";x=1024;y=768;empty"),
+            ("{?x,y,empty}", "?x=1024&y=768&empty="),
+            ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+            ("{var:3}", "val"),
+            ("{?undef}", ""),
+            ("file:///{+path}{?var}", "file:////foo/bar?var=value"),
+        ];
+
+        for (template, expected) in cases {
+            let template = UriTemplate::parse(template).unwrap();
+            assert_eq!(template.expand(&variables), expected, "template {}", template);
+        }
+    }
+
+    #[test]
+    fn test_variables_and_invalid_templates() {
+        let template = UriTemplate::parse("db://{schema}/{table}{?limit,schema}").unwrap();
+        assert_eq!(template.variables(), ["schema", "table", "limit"]);
+
+        assert!(UriTemplate::parse("file:///{path").is_err());
+        assert!(UriTemplate::parse("file:///path}").is_err());
+        assert!(UriTemplate::parse("{}").is_err());
+        assert!(UriTemplate::parse("{var:0}").is_err());
+    }
+}
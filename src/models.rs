@@ -4,6 +4,7 @@
 //! e integração com o ecossistema WARP_RULES.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,9 @@ use uuid::Uuid;
 
 use crate::error::SageXResult;
 
+// Tipos de protocolo canônicos, definidos no módulo `mcp`
+pub use crate::mcp::{McpCapabilities, McpRequest, McpResponse, TransportType};
+
 /// Identificador único para recursos SAGE-X
 pub type SageXId = Uuid;
 
@@ -239,6 +243,10 @@ pub struct McpConfig {
     
     /// Configurações de transporte
     pub transport: TransportConfig,
+    
+    /// Diretórios extras anunciados como roots, além do diretório da sessão
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
 }
 
 impl Default for McpConfig {
@@ -249,37 +257,7 @@ impl Default for McpConfig {
             server_description: "SAGE-X Rules Integration Server for MCP".to_string(),
             capabilities: McpCapabilities::default(),
             transport: TransportConfig::default(),
-        }
-    }
-}
-
-/// Capacidades do servidor MCP
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct McpCapabilities {
-    /// Suporte a tools
-    pub tools: bool,
-    
-    /// Suporte a resources
-    pub resources: bool,
-    
-    /// Suporte a prompts
-    pub prompts: bool,
-    
-    /// Suporte a notificações
-    pub notifications: bool,
-    
-    /// Suporte a streaming
-    pub streaming: bool,
-}
-
-impl Default for McpCapabilities {
-    fn default() -> Self {
-        Self {
-            tools: true,
-            resources: true,
-            prompts: true,
-            notifications: true,
-            streaming: false,
+            roots: Vec::new(),
         }
     }
 }
@@ -303,17 +281,6 @@ impl Default for TransportConfig {
     }
 }
 
-/// Tipo de transporte MCP
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum TransportType {
-    /// Standard I/O
-    Stdio,
-    /// HTTP
-    Http,
-    /// WebSocket
-    WebSocket,
-}
-
 /// Configurações de telemetria
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelemetryConfig {
@@ -672,38 +639,6 @@ pub enum SessionState {
     Interrupted,
 }
 
-/// Request para a API MCP
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct McpRequest {
-    /// ID único da requisição
-    pub id: String,
-    
-    /// Método solicitado
-    pub method: String,
-    
-    /// Parâmetros da requisição
-    pub params: Option<serde_json::Value>,
-    
-    /// Timestamp da requisição
-    pub timestamp: UnixTimestamp,
-}
-
-/// Response da API MCP
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct McpResponse {
-    /// ID da requisição correspondente
-    pub id: String,
-    
-    /// Resultado (se sucesso)
-    pub result: Option<serde_json::Value>,
-    
-    /// Erro (se falha)
-    pub error: Option<McpError>,
-    
-    /// Timestamp da resposta
-    pub timestamp: UnixTimestamp,
-}
-
 /// Erro MCP padronizado
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpError {
@@ -714,6 +649,7 @@ pub struct McpError {
     pub message: String,
     
     /// Dados adicionais
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub data: Option<serde_json::Value>,
 }
 
@@ -724,12 +660,15 @@ pub struct McpTool {
     pub name: String,
     
     /// Descrição da ferramenta
+    #[serde(default)]
     pub description: String,
     
     /// Schema de input
+    #[serde(rename = "inputSchema", alias = "input_schema")]
     pub input_schema: serde_json::Value,
     
     /// Metadados adicionais
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -743,12 +682,15 @@ pub struct McpResource {
     pub name: String,
     
     /// Descrição do resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     
     /// Tipo MIME
+    #[serde(rename = "mimeType", alias = "mime_type", default, skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
     
     /// Metadados adicionais
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -877,6 +819,38 @@ mod tests {
         assert!(!rule.can_apply(&context));
     }
 
+    #[test]
+    fn test_legacy_protocol_shapes() {
+        // Request/response antigos carregavam `timestamp`, hoje ignorado
+        let request: McpRequest = serde_json::from_value(serde_json::json!({
+            "id": "req-1",
+            "method": "tools/list",
+            "params": null,
+            "timestamp": 1_700_000_000u64,
+        })).unwrap();
+        assert_eq!(request.id, "req-1");
+        assert_eq!(request.method, "tools/list");
+
+        let response: McpResponse = serde_json::from_value(serde_json::json!({
+            "id": "req-1",
+            "result": {"ok": true},
+            "error": null,
+            "timestamp": 1_700_000_000u64,
+        })).unwrap();
+        assert!(response.is_success());
+
+        // Capacidades antigas não tinham versão, logging nem extensões
+        let capabilities: McpCapabilities = serde_json::from_value(serde_json::json!({
+            "tools": true,
+            "resources": false,
+            "prompts": true,
+            "notifications": true,
+            "streaming": false,
+        })).unwrap();
+        assert!(!capabilities.resources);
+        assert_eq!(capabilities.protocol_version, McpCapabilities::default().protocol_version);
+    }
+
     #[test]
     fn test_execution_stats_default() {
         let stats = ExecutionStats::default();
@@ -0,0 +1,358 @@
+//! Retentativas das chamadas HTTP do cliente
+//!
+//! Falhas de transporte recuperáveis (`SageXError::is_recoverable`) e as
+//! respostas 408, 429, 502, 503 e 504 são repetidas com backoff exponencial
+//! e jitter. `Retry-After` é respeitado quando presente. Por padrão apenas
+//! métodos seguros (GET, HEAD, OPTIONS) são repetidos, pois o servidor pode
+//! ter processado o request antes da falha.
+//!
+//! O [`Backoff`] define tentativas e intervalos e também é usado pela
+//! reconexão das conexões MCP.
+
+use std::time::Duration;
+
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use uuid::Uuid;
+
+use crate::error::{SageXError, SageXResult};
+use crate::models::NetworkConfig;
+
+/// Teto padrão do intervalo entre tentativas
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Backoff exponencial com jitter
+///
+/// Compartilhado pela [`RetryPolicy`] das chamadas HTTP e pela
+/// `ReconnectPolicy` das conexões MCP.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+    /// Tentativas além da primeira; zero desabilita as retentativas
+    pub max_retries: u32,
+
+    /// Intervalo antes da primeira retentativa, dobrado a cada falha
+    pub base_delay: Duration,
+
+    /// Teto do intervalo
+    pub max_delay: Duration,
+
+    /// Fração aleatória descontada de cada intervalo (0.0 a 1.0)
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::from_network(&NetworkConfig::default())
+    }
+}
+
+impl Backoff {
+    /// Usa `max_retries` e `retry_delay` da configuração de rede
+    pub fn from_network(network: &NetworkConfig) -> Self {
+        Self {
+            max_retries: network.max_retries,
+            base_delay: network.retry_delay,
+            max_delay: DEFAULT_MAX_DELAY.max(network.retry_delay),
+            jitter: 0.5,
+        }
+    }
+
+    /// Backoff sem retentativas
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Define o número de retentativas
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Define o intervalo inicial e o teto
+    pub fn with_delays(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay.max(base_delay);
+        self
+    }
+
+    /// Define a fração de jitter
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Verifica se há retentativas
+    pub fn is_enabled(&self) -> bool {
+        self.max_retries > 0
+    }
+
+    /// Intervalo sem jitter antes da tentativa (a partir de 1): `base * 2^(attempt - 1)`
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.base_delay.checked_mul(1 << exponent).unwrap_or(self.max_delay).min(self.max_delay)
+    }
+
+    /// Intervalo antes da tentativa, descontada uma fração aleatória de até `jitter`
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let random = (Uuid::new_v4().as_u128() % 10_000) as f64 / 10_000.0;
+        self.backoff(attempt).mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+/// Política de retentativa das chamadas HTTP
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetryPolicy {
+    /// Número de tentativas e intervalos
+    pub backoff: Backoff,
+
+    /// Repetir também métodos não seguros (POST, PUT, ...)
+    pub retry_unsafe_methods: bool,
+}
+
+impl From<Backoff> for RetryPolicy {
+    fn from(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            retry_unsafe_methods: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Repete também métodos não seguros; use apenas com endpoints idempotentes
+    pub fn retry_unsafe_methods(mut self) -> Self {
+        self.retry_unsafe_methods = true;
+        self
+    }
+
+    /// Verifica se requests com o método podem ser repetidos
+    pub fn allows(&self, method: &Method) -> bool {
+        self.retry_unsafe_methods || matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+    }
+}
+
+/// Verifica se o status indica falha transitória
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Lê o `Retry-After` em segundos ou como data HTTP
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let remaining = date.signed_duration_since(chrono::Utc::now());
+    Some(remaining.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Falha de uma tentativa
+struct Failure {
+    error: SageXError,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
+
+/// Executa uma tentativa, convertendo respostas sem sucesso em erro
+async fn attempt(request: RequestBuilder, operation: &str) -> Result<Response, Failure> {
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let error = if e.is_builder() {
+                SageXError::configuration(format!("{}: {}", operation, e))
+            } else {
+                SageXError::connection(format!("{}: {}", operation, e))
+            };
+            return Err(Failure {
+                retryable: error.is_recoverable(),
+                error,
+                retry_after: None,
+            });
+        }
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after(&response);
+    let error = response.error_for_status().map_or_else(
+        |e| SageXError::Http(e.to_string()),
+        |_| SageXError::Http(format!("{} retornou {}", operation, status)),
+    );
+    Err(Failure {
+        error,
+        retryable: is_transient(status),
+        retry_after,
+    })
+}
+
+/// Envia o request aplicando a política de retentativa
+///
+/// Respostas sem sucesso viram `SageXError::Http`. O erro final informa no
+/// contexto quantas tentativas foram feitas.
+pub async fn send_with_retry(
+    policy: &RetryPolicy,
+    request: RequestBuilder,
+    operation: &str,
+) -> SageXResult<Response> {
+    // Requests com corpo em stream não podem ser clonados e nunca são repetidos
+    let method = request.try_clone().and_then(|r| r.build().ok()).map(|r| r.method().clone());
+    let max_attempts = match method {
+        Some(method) if policy.allows(&method) => policy.backoff.max_retries + 1,
+        _ => 1,
+    };
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let current = match request.try_clone() {
+            Some(current) if attempts < max_attempts => current,
+            _ => return attempt(request, operation).await.map_err(|f| f.error.with_context(tried(attempts))),
+        };
+
+        let failure = match attempt(current, operation).await {
+            Ok(response) => return Ok(response),
+            Err(failure) => failure,
+        };
+        if !failure.retryable {
+            return Err(failure.error.with_context(tried(attempts)));
+        }
+
+        let delay = match failure.retry_after {
+            Some(retry_after) if retry_after > policy.backoff.max_delay => {
+                log::warn!("{}: Retry-After de {:?} excede o limite de retentativa", operation, retry_after);
+                return Err(failure.error.with_context(tried(attempts)));
+            }
+            Some(retry_after) => retry_after,
+            None => policy.backoff.delay(attempts),
+        };
+        log::warn!(
+            "{} falhou (tentativa {}/{}): {}; repetindo em {:?}",
+            operation, attempts, max_attempts, failure.error, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn tried(attempts: u32) -> String {
+    match attempts {
+        1 => "1 tentativa".to_string(),
+        n => format!("{} tentativas", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Servidor HTTP que responde, em ordem, as respostas brutas informadas
+    /// (repetindo a última) e conta os requests recebidos
+    async fn scripted_server(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let response = responses[index.min(responses.len() - 1)];
+                let mut buffer = [0u8; 4096];
+                let _ = socket.read(&mut buffer).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, hits)
+    }
+
+    const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+    const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    fn fast_policy() -> RetryPolicy {
+        Backoff::default()
+            .with_max_retries(2)
+            .with_delays(Duration::from_millis(5), Duration::from_millis(20))
+            .into()
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_bounded_and_jittered() {
+        let backoff = Backoff::default()
+            .with_delays(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(0.5);
+
+        assert_eq!(backoff.backoff(1), Duration::from_millis(100));
+        assert_eq!(backoff.backoff(2), Duration::from_millis(200));
+        assert_eq!(backoff.backoff(4), Duration::from_millis(800));
+        assert_eq!(backoff.backoff(5), Duration::from_secs(1));
+        assert_eq!(backoff.backoff(200), Duration::from_secs(1));
+
+        for attempt in 1..6 {
+            let delay = backoff.delay(attempt);
+            assert!(delay <= backoff.backoff(attempt));
+            assert!(delay >= backoff.backoff(attempt) / 2);
+        }
+
+        let network = NetworkConfig::default();
+        let backoff = Backoff::from_network(&network);
+        assert_eq!(backoff.max_retries, network.max_retries);
+        assert_eq!(backoff.base_delay, network.retry_delay);
+        assert!(!Backoff::disabled().is_enabled());
+    }
+
+    #[test]
+    fn test_retry_methods() {
+        let policy = RetryPolicy::default();
+        assert!(policy.allows(&Method::GET));
+        assert!(!policy.allows(&Method::POST));
+        assert!(policy.retry_unsafe_methods().allows(&Method::POST));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_status_honoring_retry_after() {
+        let (url, hits) = scripted_server(vec![UNAVAILABLE, UNAVAILABLE, OK]).await;
+        let client = reqwest::Client::new();
+
+        let response = send_with_retry(&fast_policy(), client.get(&url), "GET /health").await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_with_attempt_count() {
+        let (url, hits) = scripted_server(vec![UNAVAILABLE]).await;
+        let client = reqwest::Client::new();
+
+        let error = send_with_retry(&fast_policy(), client.get(&url), "GET /rules").await.unwrap_err();
+        assert!(matches!(error, SageXError::Http(_)));
+        assert!(error.to_string().ends_with("(3 tentativas)"), "{}", error);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        // Métodos não seguros e status permanentes não são repetidos
+        let (url, hits) = scripted_server(vec![UNAVAILABLE]).await;
+        assert!(send_with_retry(&fast_policy(), client.post(&url), "POST /rules").await.is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (url, hits) = scripted_server(vec![NOT_FOUND]).await;
+        let error = send_with_retry(&fast_policy(), client.get(&url), "GET /rules").await.unwrap_err();
+        assert!(error.to_string().ends_with("(1 tentativa)"), "{}", error);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,100 @@
+#!/usr/bin/env python3
+"""Captura uma transcrição JSON-RPC real de um servidor MCP via stdio.
+
+Uso (requer Node.js e acesso ao registro npm):
+
+    python3 tests/fixtures/jsonrpc/capture.py
+
+Executa `@modelcontextprotocol/server-everything`, envia initialize,
+notifications/initialized, tools/list, tools/call (echo e
+longRunningOperation com progressToken) e um método inexistente, e grava
+cada mensagem trocada em `captured/everything/transcript.jsonl`, junto com
+a origem em `captured/everything/SOURCE.md`.
+"""
+
+import datetime
+import json
+import pathlib
+import subprocess
+import sys
+import time
+
+PACKAGE = "@modelcontextprotocol/server-everything"
+PROTOCOL_VERSION = "2025-06-18"
+OUTPUT = pathlib.Path(__file__).parent / "captured" / "everything"
+
+REQUESTS = [
+    ("initialize", {
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {"roots": {"listChanged": True}, "sampling": {}},
+        "clientInfo": {"name": "sage-x-fixture-capture", "version": "0.1.0"},
+    }),
+    ("tools/list", {}),
+    ("tools/call", {"name": "echo", "arguments": {"message": "hello"}}),
+    ("tools/call", {
+        "name": "longRunningOperation",
+        "arguments": {"duration": 1, "steps": 2},
+        "_meta": {"progressToken": "capture-progress"},
+    }),
+    ("sage-x/unknown", {}),
+]
+
+
+def main():
+    server = subprocess.Popen(
+        ["npx", "-y", PACKAGE, "stdio"],
+        stdin=subprocess.PIPE,
+        stdout=subprocess.PIPE,
+        text=True,
+    )
+    transcript = []
+
+    def send(message):
+        transcript.append({"direction": "client", "message": message})
+        server.stdin.write(json.dumps(message) + "\n")
+        server.stdin.flush()
+
+    def receive_until(request_id):
+        while True:
+            line = server.stdout.readline()
+            if not line:
+                sys.exit("servidor encerrou antes de responder {}".format(request_id))
+            message = json.loads(line)
+            transcript.append({"direction": "server", "message": message})
+            if message.get("id") == request_id and "method" not in message:
+                return message
+
+    initialize = None
+    for request_id, (method, params) in enumerate(REQUESTS, start=1):
+        send({"jsonrpc": "2.0", "id": request_id, "method": method, "params": params})
+        response = receive_until(request_id)
+        if method == "initialize":
+            initialize = response
+            send({"jsonrpc": "2.0", "method": "notifications/initialized"})
+
+    # Dá tempo para notificações espontâneas do servidor (logs, updates)
+    time.sleep(2)
+    server.stdin.close()
+    server.terminate()
+    for line in server.stdout.read().splitlines():
+        if line.strip():
+            transcript.append({"direction": "server", "message": json.loads(line)})
+
+    OUTPUT.mkdir(parents=True, exist_ok=True)
+    with open(OUTPUT / "transcript.jsonl", "w") as out:
+        for entry in transcript:
+            out.write(json.dumps(entry, ensure_ascii=False) + "\n")
+
+    server_info = initialize["result"]["serverInfo"]
+    node = subprocess.run(["node", "--version"], capture_output=True, text=True).stdout.strip()
+    with open(OUTPUT / "SOURCE.md", "w") as out:
+        out.write("# Origem\n\n")
+        out.write("- Pacote: `{}` (serverInfo: `{}` {})\n".format(PACKAGE, server_info["name"], server_info["version"]))
+        out.write("- Comando: `npx -y {} stdio`\n".format(PACKAGE))
+        out.write("- Node.js: {}\n".format(node))
+        out.write("- Protocolo solicitado: {}\n".format(PROTOCOL_VERSION))
+        out.write("- Capturado em: {}\n".format(datetime.date.today().isoformat()))
+
+
+if __name__ == "__main__":
+    main()